@@ -14,7 +14,7 @@ pub fn construct_derive(input: ::proc_macro::TokenStream) -> ::proc_macro::Token
     ::proc_macro::TokenStream::from(stream)
 }
 
-#[proc_macro_derive(Segment, attributes(prop, param))]
+#[proc_macro_derive(Segment, attributes(prop, param, construct, segment))]
 pub fn segment_derive(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
     use ::constructivist::prelude::*;
     use ::syn::{parse_macro_input, DeriveInput};
@@ -32,6 +32,25 @@ pub fn segment_derive(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenSt
     ::proc_macro::TokenStream::from(stream)
 }
 
+#[proc_macro_attribute]
+pub fn virtual_design(
+    attr: ::proc_macro::TokenStream,
+    item: ::proc_macro::TokenStream,
+) -> ::proc_macro::TokenStream {
+    use ::constructivist::prelude::*;
+    type ConstructivismContext = Context;
+    let ctx = ConstructivismContext::new("constructivism");
+    let design = match VirtualDesign::from_attr(attr.into(), item.into()) {
+        Err(e) => return ::proc_macro::TokenStream::from(e.to_compile_error()),
+        Ok(d) => d,
+    };
+    let stream = match design.build(&ctx) {
+        Err(e) => return ::proc_macro::TokenStream::from(e.to_compile_error()),
+        Ok(c) => c,
+    };
+    ::proc_macro::TokenStream::from(stream)
+}
+
 #[proc_macro]
 pub fn derive_construct(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
     use ::constructivist::prelude::*;
@@ -82,6 +101,24 @@ pub fn construct(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream
     //     Err(e) => e.to_compile_error(),
     // })
 }
+#[proc_macro]
+pub fn update(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
+    use ::constructivist::prelude::*;
+    use ::syn::parse_macro_input;
+    type ConstructivismValue = syn::Expr;
+    type ConstructivismContext = Context;
+    let upd = parse_macro_input!(input as Update<ConstructivismValue>);
+    ::proc_macro::TokenStream::from(
+        match ::constructivist::proc::build(
+            ConstructivismContext::new("constructivism"),
+            move |ctx| upd.build(ctx),
+        ) {
+            Ok(r) => r,
+            Err(e) => e.to_compile_error(),
+        },
+    )
+}
+
 #[proc_macro]
 pub fn prop(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
     use ::constructivist::prelude::*;
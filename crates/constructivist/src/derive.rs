@@ -6,11 +6,13 @@ use crate::throw;
 use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::{format_ident, quote, ToTokens};
 use syn::{
-    braced, bracketed, parenthesized,
+    braced, bracketed,
+    ext::IdentExt,
+    parenthesized,
     parse::{Parse, ParseStream},
     parse2, parse_quote,
     spanned::Spanned,
-    Attribute, Data, DeriveInput, Expr, Field, Fields, Ident, Token, Type,
+    Attribute, Data, DeriveInput, Expr, Field, Fields, Ident, RangeLimits, Token, Type,
 };
 
 pub struct Declarations {
@@ -50,10 +52,17 @@ impl Declarations {
             Ok(T::default())
         }
     }
+    pub fn parse_optional<T: Parse>(&self, key: &str) -> syn::Result<Option<T>> {
+        self.decls
+            .get(key)
+            .map(|stream| parse2(stream.clone()))
+            .transpose()
+    }
 }
 
+// `Type` is boxed to keep the size of the variants close
 pub enum ParamType {
-    Single(Type),
+    Single(Box<Type>),
     Union(Vec<Param>),
 }
 impl Parse for ParamType {
@@ -64,7 +73,7 @@ impl Parse for ParamType {
             let params = content.parse_terminated(Param::parse, Token![,])?;
             Ok(ParamType::Union(params.into_iter().collect()))
         } else {
-            Ok(ParamType::Single(input.parse()?))
+            Ok(ParamType::Single(Box::new(input.parse()?)))
         }
     }
 }
@@ -91,8 +100,73 @@ impl Parse for ParamKind {
                 Ok(ParamKind::Skip(parse_quote!(Default::default())))
             }
         } else {
-            throw!(ident, "Expected required|default|skip");
+            throw!(ident, "Expected required|default|skip|reset");
+        }
+    }
+}
+
+pub enum ParamSpecItem {
+    Kind(ParamKind),
+    Reset(Option<Expr>),
+}
+impl Parse for ParamSpecItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: Ident = input.fork().parse()?;
+        if &ident.to_string() != "reset" {
+            return Ok(ParamSpecItem::Kind(input.parse()?));
+        }
+        input.parse::<Ident>()?;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Ok(ParamSpecItem::Reset(Some(input.parse()?)))
+        } else {
+            Ok(ParamSpecItem::Reset(None))
+        }
+    }
+}
+
+/// `#[param(skip = 0., reset)]`
+pub struct ParamSpec {
+    pub kind: ParamKind,
+    // Some(None) for plain `reset`: reset to the kind's value
+    pub reset: Option<Option<Expr>>,
+}
+impl ParamSpec {
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut spec = ParamSpec {
+            kind: ParamKind::Common,
+            reset: None,
+        };
+        for attr in attrs.iter().filter(|a| a.path().is_ident("param")) {
+            let items = attr.parse_args_with(
+                syn::punctuated::Punctuated::<ParamSpecItem, Token![,]>::parse_terminated,
+            )?;
+            for item in items {
+                match item {
+                    ParamSpecItem::Kind(kind) => spec.kind = kind,
+                    ParamSpecItem::Reset(reset) => spec.reset = Some(reset),
+                }
+            }
         }
+        Ok(spec)
+    }
+
+    /// The value `Reset::reset_defaults` writes to the field, `None` to leave it alone.
+    pub fn reset(&self, name: &Ident) -> syn::Result<Option<Expr>> {
+        Ok(match (&self.kind, &self.reset) {
+            (_, Some(Some(expr))) => Some(expr.clone()),
+            (ParamKind::Common, _) => Some(parse_quote!(Default::default())),
+            (ParamKind::Default(expr), _) => Some(expr.clone()),
+            (ParamKind::Skip(expr), Some(None)) => Some(expr.clone()),
+            (ParamKind::Skip(_), None) => None,
+            (ParamKind::Required, None) => None,
+            (ParamKind::Required, Some(None)) => {
+                throw!(
+                    name,
+                    "Required param has no default value, use `#[param(required, reset = ..)]`"
+                );
+            }
+        })
     }
 }
 
@@ -100,6 +174,7 @@ pub struct Param {
     pub name: Ident,
     pub ty: ParamType,
     pub kind: ParamKind,
+    pub reset: Option<Expr>,
     pub docs: Vec<Attribute>,
 }
 
@@ -119,10 +194,15 @@ impl Parse for Param {
         } else {
             ParamKind::Required
         };
+        let reset = match &kind {
+            ParamKind::Default(expr) => Some(expr.clone()),
+            _ => None,
+        };
         Ok(Param {
             name,
             ty,
             kind,
+            reset,
             docs,
         })
     }
@@ -153,7 +233,36 @@ pub struct BuildedParams {
     param_values: TokenStream,
     impls: TokenStream,
     fields: TokenStream,
+    // pub min: Param<Option<min>, f32>, for the optional segments
+    optional_fields: TokenStream,
     fields_new: TokenStream,
+    // self.min = 0., self.max = 1.,
+    resets: TokenStream,
+    // slider_construct::min(0.), slider_construct::max(1.), or None when some param is required
+    reset_params: Option<TokenStream>,
+}
+impl BuildedParams {
+    /// Fields are assigned directly by derives, custom constructors are called again
+    /// with default params.
+    pub fn build_reset(&self, ctx: &Context, ty: &Type, custom: bool) -> Option<TokenStream> {
+        let lib = ctx.path("constructivism");
+        let body = if !custom {
+            self.resets.clone()
+        } else if let Some(params) = &self.reset_params {
+            quote! {
+                *self = <Self as #lib::ConstructItem>::construct_item((#params));
+            }
+        } else {
+            return None;
+        };
+        Some(quote! {
+            impl #lib::Reset for #ty {
+                fn reset_defaults(&mut self) {
+                    #body
+                }
+            }
+        })
+    }
 }
 pub trait Params: Sized {
     fn from_fields(fields: &syn::Fields, name: &str, alter: &str) -> syn::Result<Self>;
@@ -163,7 +272,7 @@ impl Params for Vec<Param> {
     fn from_fields(fields: &syn::Fields, name: &str, alter: &str) -> syn::Result<Self> {
         let mut params = vec![];
         for field in fields.iter() {
-            let ty = ParamType::Single(field.ty.clone());
+            let ty = ParamType::Single(Box::new(field.ty.clone()));
             let docs = field
                 .attrs
                 .iter()
@@ -173,18 +282,14 @@ impl Params for Vec<Param> {
             let Some(name) = field.ident.clone() else {
                 throw!(field, "#[derive({})] only supports named structs. You can use `{}!` for complex cases.", name, alter);
             };
-            let kind = field
-                .attrs
-                .iter()
-                .filter(|a| a.path().is_ident("param"))
-                .map(|a| a.parse_args())
-                .last()
-                .or(Some(Ok(ParamKind::Common)))
-                .unwrap()?;
+            let spec = ParamSpec::from_attrs(&field.attrs)?;
+            let reset = spec.reset(&name)?;
+            let kind = spec.kind;
             params.push(Param {
                 ty,
                 name,
                 kind,
+                reset,
                 docs,
             });
         }
@@ -198,13 +303,27 @@ impl Params for Vec<Param> {
         let mut param_values = quote! {}; // min, max, val,
         let mut impls = quote! {};
         let mut fields = quote! {};
+        let mut optional_fields = quote! {};
         let mut fields_new = quote! {};
+        let mut resets = quote! {};
+        let mut reset_params = Some(quote! {});
         for param in self.iter() {
             let ParamType::Single(param_ty) = &param.ty else {
                 throw!(ty, "Union params not supported yet.");
             };
             let ident = &param.name;
             let docs = param.docs();
+            if let Some(reset) = &param.reset {
+                resets = quote! { #resets self.#ident = #reset; };
+            }
+            if param.skip().is_none() {
+                reset_params = match (reset_params, &param.reset) {
+                    (Some(params), Some(reset)) => {
+                        Some(quote! { #params #mod_ident::#ident(#reset), })
+                    }
+                    _ => None,
+                };
+            }
             if let Some(skip) = param.skip() {
                 param_values = quote! { #param_values #ident: #skip, };
             } else {
@@ -217,6 +336,11 @@ impl Params for Vec<Param> {
                     #docs
                     pub #ident: #lib::Param<#ident, #param_ty>,
                 };
+                optional_fields = quote! { #optional_fields
+                    #[allow(unused_variables)]
+                    #docs
+                    pub #ident: #lib::Param<Option<#ident>, #param_ty>,
+                };
                 fields_new =
                     quote! { #fields_new #ident: #lib::Param(::std::marker::PhantomData), };
                 let default = match &param.kind {
@@ -245,8 +369,27 @@ impl Params for Vec<Param> {
                         throw!(skip, "Unexpected skip param");
                     }
                 };
+                let param_default = if matches!(param.kind, ParamKind::Required) {
+                    quote! {
+                        impl #lib::ParamDefault for #ident {
+                            fn param_default() -> Option<Self> {
+                                None
+                            }
+                        }
+                    }
+                } else {
+                    quote! {
+                        impl #lib::ParamDefault for #ident {
+                            fn param_default() -> Option<Self> {
+                                Some(Default::default())
+                            }
+                        }
+                        impl #lib::OptionalParam for #ident { }
+                    }
+                };
                 impls = quote! { #impls
                     #default
+                    #param_default
                     #[allow(non_camel_case_types)]
                     pub struct #ident(pub #param_ty);
                     impl<T: Into<#param_ty>> From<T> for #ident {
@@ -273,42 +416,210 @@ impl Params for Vec<Param> {
             param_values,
             impls,
             fields,
+            optional_fields,
             fields_new,
+            resets,
+            reset_params,
         })
     }
 }
 
 pub struct Sequence {
     pub this: Type,
-    pub segments: Vec<Type>,
+    pub segments: Vec<SequenceSegment>,
     pub next: Type,
 }
 
+/// Segment of the sequence, `?Tooltip` is optional and held as `Option<Tooltip>`.
+pub struct SequenceSegment {
+    pub ty: Type,
+    pub optional: bool,
+}
+
+impl SequenceSegment {
+    /// The type of the level in the nested sequence.
+    pub fn item(&self) -> TokenStream {
+        let ty = &self.ty;
+        if self.optional {
+            quote! { Option<#ty> }
+        } else {
+            quote! { #ty }
+        }
+    }
+}
+
+impl Parse for SequenceSegment {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let optional = input.parse::<Option<Token![?]>>()?.is_some();
+        let ty = input.parse()?;
+        Ok(SequenceSegment { ty, optional })
+    }
+}
+
 impl Parse for Sequence {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let this = input.parse()?;
         input.parse::<Token![->]>()?;
-        let mut next = input.parse()?;
+        let mut next: SequenceSegment = input.parse()?;
         let mut segments = vec![];
         while input.peek(Token![->]) {
             input.parse::<Token![->]>()?;
             segments.push(next);
             next = input.parse()?;
         }
+        if next.optional {
+            throw!(next.ty, "The base of the sequence can't be optional.");
+        }
         Ok(Sequence {
             this,
             segments,
-            next,
+            next: next.ty,
         })
     }
 }
 
+/// Opt-in features of the derive: `#[construct(debug)]`, `#[construct(overrides = Expand)]`
+#[derive(Default)]
+pub struct ConstructFlags {
+    pub debug: bool,
+    /// `#[construct(abstract)]`, the construct can only be extended.
+    pub is_abstract: bool,
+    /// `#[construct(final)]`, the construct can't be extended.
+    pub is_final: bool,
+    /// `#[virtual_design]` traits implemented by the design of this level.
+    pub overrides: Vec<Type>,
+    /// Traits available through `design_as()`: `#[construct(provides = Focusable)]`
+    pub provides: Vec<Type>,
+    /// User defined design type: `#[construct(design = ButtonStyle)]`
+    pub design: Option<Type>,
+    /// Per item hook: `#[construct(after = register)]`
+    pub after: Option<Expr>,
+    /// Sequence hook: `#[construct(finalize = layout)]`
+    pub finalize: Option<Expr>,
+    /// `#[construct(hooks = top_down)]`, the `after` hooks run bottom up by default.
+    pub top_down: bool,
+}
+
+impl ConstructFlags {
+    pub const FLAGS: &'static [&'static str] = &["debug", "abstract", "final"];
+    pub const KEYS: &'static [&'static str] = &[
+        "overrides",
+        "provides",
+        "design",
+        "after",
+        "finalize",
+        "hooks",
+    ];
+
+    fn parse_flags(attr: &Attribute) -> syn::Result<Vec<PropSpecItem>> {
+        let flags = attr.parse_args_with(
+            syn::punctuated::Punctuated::<PropSpecItem, Token![,]>::parse_terminated,
+        )?;
+        Ok(flags.into_iter().collect())
+    }
+
+    /// `#[construct(..)]` with flags only, not the sequence.
+    pub fn is_flags(attr: &Attribute) -> bool {
+        Self::parse_flags(attr)
+            .map(|flags| {
+                flags.iter().all(|f| match f {
+                    PropSpecItem::Flag(f) => Self::FLAGS.contains(&f.to_string().as_str()),
+                    PropSpecItem::Value(k, _) => Self::KEYS.contains(&k.to_string().as_str()),
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// `ConstructItem::provide_design` for the `overrides` and `provides` lists.
+    pub fn build_provide(&self, ctx: &Context) -> TokenStream {
+        let lib = ctx.constructivism();
+        if self.overrides.is_empty() && self.provides.is_empty() {
+            return quote! {};
+        }
+        let mut provides = quote! {};
+        for ty in self.overrides.iter().chain(self.provides.iter()) {
+            provides = quote! { #provides
+                if let Some(__slot__) = __slot__.downcast_mut::<Option<&'static dyn #ty>>() {
+                    *__slot__ = Some(<<Self as #lib::Construct>::Design as #lib::Singleton>::instance());
+                    return true;
+                }
+            };
+        }
+        quote! {
+            fn provide_design(__slot__: &mut dyn ::std::any::Any) -> bool {
+                #provides
+                false
+            }
+        }
+    }
+
+    /// `ConstructItem::after_construct` for the `after` hook.
+    pub fn build_after(&self) -> TokenStream {
+        if let Some(after) = &self.after {
+            quote! {
+                fn after_construct(&mut self) {
+                    #after(self)
+                }
+            }
+        } else {
+            quote! {}
+        }
+    }
+
+    /// Reads `#[construct(..)]` flags, `allow_sequence` skips the sequence declaration.
+    pub fn from_attrs(attrs: &[Attribute], allow_sequence: bool) -> syn::Result<Self> {
+        let mut out = ConstructFlags::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("construct")) {
+            if allow_sequence && !Self::is_flags(attr) {
+                continue;
+            }
+            for flag in Self::parse_flags(attr)? {
+                match flag {
+                    PropSpecItem::Flag(flag) if flag == "debug" => out.debug = true,
+                    PropSpecItem::Flag(flag) if flag == "abstract" => out.is_abstract = true,
+                    PropSpecItem::Flag(flag) if flag == "final" => out.is_final = true,
+                    PropSpecItem::Value(key, value) if key == "overrides" => {
+                        out.overrides.push(parse2(value.to_token_stream())?)
+                    }
+                    PropSpecItem::Value(key, value) if key == "provides" => {
+                        out.provides.push(parse2(value.to_token_stream())?)
+                    }
+                    PropSpecItem::Value(key, value) if key == "design" => {
+                        out.design = Some(parse2(value.to_token_stream())?)
+                    }
+                    PropSpecItem::Value(key, value) if key == "after" => out.after = Some(value),
+                    PropSpecItem::Value(key, value) if key == "finalize" => {
+                        out.finalize = Some(value)
+                    }
+                    PropSpecItem::Value(key, value) if key == "hooks" => {
+                        match value.to_token_stream().to_string().as_str() {
+                            "top_down" => out.top_down = true,
+                            "bottom_up" => out.top_down = false,
+                            _ => {
+                                throw!(value, "Expected #[construct(hooks = top_down|bottom_up)]");
+                            }
+                        }
+                    }
+                    PropSpecItem::Flag(flag) | PropSpecItem::Value(flag, _) => {
+                        throw!(
+                            flag,
+                            "Unexpected #[construct] flag, expected one of: {}",
+                            [Self::FLAGS, Self::KEYS].concat().join(", ")
+                        );
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
 impl Sequence {
     pub fn from_derive(input: &DeriveInput) -> syn::Result<Self> {
         let attrs = input
             .attrs
             .iter()
-            .filter(|a| a.path().is_ident("construct"))
+            .filter(|a| a.path().is_ident("construct") && !ConstructFlags::is_flags(a))
             .collect::<Vec<_>>();
         if attrs.len() == 0 {
             let ty = &input.ident;
@@ -330,6 +641,9 @@ pub struct DeriveSegment {
     params: Vec<Param>,
     props: Props,
     body: Option<Expr>,
+    flags: ConstructFlags,
+    /// The base the segment can be inserted on, `#[segment(requires = Rect)]`
+    requires: Option<Type>,
 }
 
 impl Parse for DeriveSegment {
@@ -340,11 +654,14 @@ impl Parse for DeriveSegment {
         let params = constructor.params;
         let body = Some(constructor.expr);
         let props = decls.parse_or_default("props")?;
+        let requires = decls.parse_optional("requires")?;
         Ok(DeriveSegment {
             ty,
             params,
             body,
             props,
+            flags: ConstructFlags::default(),
+            requires,
         })
     }
 }
@@ -358,18 +675,56 @@ impl DeriveSegment {
             );
         }
         let ty = &input.ident;
-        let ty = syn::parse2(quote! { #ty })?;
+        let ty: Type = syn::parse2(quote! { #ty })?;
+        let attrs = input.attrs.clone();
         let Data::Struct(input) = input.data else {
             throw!(input.ident, "#[derive(Segment)] only supports named structs. You can use `derive_segment!` for complex cases.");
         };
         let params = Params::from_fields(&input.fields, "Segment", "derive_segment")?;
         let body = None;
-        let props = Props::from_fields(&input.fields)?;
+        let props = Props::from_derive(&attrs, &input.fields)?;
+        let flags = ConstructFlags::from_attrs(&attrs, false)?;
+        if let Some(design) = &flags.design {
+            throw!(
+                design,
+                "#[construct(design = ..)] is not supported for segments yet."
+            );
+        }
+        if let Some(finalize) = &flags.finalize {
+            throw!(
+                finalize,
+                "#[construct(finalize = ..)] is not supported for segments, use `after`."
+            );
+        }
+        if flags.is_abstract || flags.is_final {
+            throw!(
+                ty,
+                "#[construct(abstract)] and #[construct(final)] are not supported for segments."
+            );
+        }
+        let mut requires = None;
+        for attr in attrs.iter().filter(|a| a.path().is_ident("segment")) {
+            let items = attr.parse_args_with(
+                syn::punctuated::Punctuated::<PropSpecItem, Token![,]>::parse_terminated,
+            )?;
+            for item in items {
+                match item {
+                    PropSpecItem::Value(key, value) if key == "requires" => {
+                        requires = Some(parse2(value.to_token_stream())?)
+                    }
+                    PropSpecItem::Flag(flag) | PropSpecItem::Value(flag, _) => {
+                        throw!(flag, "Unexpected #[segment] flag, expected: requires");
+                    }
+                }
+            }
+        }
         Ok(DeriveSegment {
             ty,
             params,
             props,
             body,
+            flags,
+            requires,
         })
     }
 
@@ -383,19 +738,55 @@ impl DeriveSegment {
             type_ident.to_string().to_lowercase()
         );
         let design = format_ident!("{type_ident}Design");
+        let builded = self.params.build(ctx, &ty, &mod_ident)?;
+        let reset = builded.build_reset(ctx, ty, self.body.is_some());
         let BuildedParams {
             fields,
+            optional_fields,
             fields_new,
             impls,
             param_values,
             type_params,
             type_params_deconstruct,
-        } = self.params.build(ctx, &ty, &mod_ident)?;
+            ..
+        } = builded;
         let props_getters = self.props.build_lookup_getters(ctx, &ty)?;
         let props_setters = self.props.build_lookup_setters(ctx, &ty)?;
         let props_descriptors = self.props.build_type_descriptors(ctx, &ty)?;
         let getters = self.props.build_getters(ctx)?;
-        let setters = self.props.build_setters(ctx)?;
+        let setters = self.props.build_setters(ctx, ty)?;
+        let diff = self.props.build_diff(ctx, ty)?;
+        let visit = self.props.build_visit(ctx, ty);
+        let debug = if self.flags.debug {
+            self.props.build_debug(ctx, ty)?
+        } else {
+            quote! {}
+        };
+        let update = self
+            .props
+            .build_update(ctx, ty, &self.params, self.body.is_some(), true)?;
+        let constraints = self.props.build_param_constraints(ctx, ty, &self.params)?;
+        let infos = self.props.build_infos(ctx, ty);
+        let provide = self.flags.build_provide(ctx);
+        let after = self.flags.build_after();
+        let requires = self
+            .requires
+            .as_ref()
+            .map(|requires| quote! { #requires })
+            .unwrap_or(quote! { () });
+        let required = if let Some(requires) = &self.requires {
+            quote! {
+                impl<T> #design<T> {
+                    /// The static design of the required construct. It is not the design of
+                    /// the actual base, the overrides of the derived constructs are not visible.
+                    pub fn required(&self) -> &'static <#requires as #lib::Construct>::Design {
+                        <<#requires as #lib::Construct>::Design as #lib::Singleton>::instance()
+                    }
+                }
+            }
+        } else {
+            quote! {}
+        };
         let construct = if let Some(expr) = &self.body {
             expr.clone()
         } else {
@@ -426,6 +817,24 @@ impl DeriveSegment {
                     T::instance()
                 }
             }
+            pub struct OptionalFields<T: #lib::Singleton> {
+                #optional_fields
+                __base__: ::std::marker::PhantomData<T>,
+            }
+            impl<T: #lib::Singleton> #lib::Singleton for OptionalFields<T> {
+                fn instance() -> &'static Self {
+                    &OptionalFields {
+                        #fields_new
+                        __base__: ::std::marker::PhantomData,
+                    }
+                }
+            }
+            impl<T: #lib::Singleton + 'static> std::ops::Deref for OptionalFields<T> {
+                type Target = T;
+                fn deref(&self) -> &Self::Target {
+                    T::instance()
+                }
+            }
 
             // Props
             pub struct TypeReference;
@@ -497,7 +906,10 @@ impl DeriveSegment {
                 }
             }
             impl<M: 'static, T: #lib::Props<M>> #lib::Props<M> for Props<M, T> { }
-
+            #diff
+            #visit
+            #debug
+            #update
 
         };
         Ok(quote! {
@@ -510,14 +922,31 @@ impl DeriveSegment {
                 type Params = ( #type_params );
                 type Getters<'a> = #mod_ident::Getters<'a>;
                 type Setters<'a> = #mod_ident::Setters<'a>;
+                #infos
+                #provide
+                #after
                 fn construct_item(params: Self::Params) -> Self {
                     let (#type_params_deconstruct) = params;
+                    #constraints
                     #construct
                 }
             }
+            impl #lib::Standalone for #type_ident { }
+            #reset
+            impl #lib::Through for #type_ident {
+                type Target = Self;
+                fn through<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+                    f(self)
+                }
+                fn through_mut<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+                    f(self)
+                }
+            }
             impl #lib::Segment for #type_ident {
+                type Requires = #requires;
                 type Props<M: 'static, T: #lib::Props<M> + 'static> = #mod_ident::Props<M, T>;
                 type Params<T: #lib::Singleton + 'static> = #mod_ident::Params<T>;
+                type OptionalFields<T: #lib::Singleton + 'static> = #mod_ident::OptionalFields<T>;
                 type Design<T: #lib::Singleton + 'static> = #design<T>;
             }
             pub struct #design<T>(
@@ -534,6 +963,7 @@ impl DeriveSegment {
                     T::instance()
                 }
             }
+            #required
         })
     }
 }
@@ -542,22 +972,192 @@ pub struct Prop {
     pub ident: Ident,
     pub ty: Type,
     pub kind: PropKind,
+    /// Method called after the value is set, `#[prop(commit = normalize)]`
+    pub commit: Option<Ident>,
+    pub meta: PropMeta,
     docs: Vec<Attribute>,
 }
 
+/// Constraints and editor metadata: `#[prop(range = 0.0..=1.0, step = 0.01, unit = "px")]`
+#[derive(Default)]
+pub struct PropMeta {
+    pub range: Option<Expr>,
+    pub step: Option<Expr>,
+    pub unit: Option<Expr>,
+    /// `#[prop(on_invalid = reject)]` ignores out of range values instead of clamping them.
+    pub reject: bool,
+    /// `#[prop(tag = "style", tag = "color")]`
+    pub tags: Vec<Expr>,
+    /// `#[prop(group = "Layout")]`
+    pub group: Option<Expr>,
+}
+
+impl PropMeta {
+    pub fn from_spec(spec: &PropSpec) -> syn::Result<Self> {
+        let reject = match spec.ident("on_invalid")? {
+            None => false,
+            Some(ident) if &ident.to_string() == "clamp" => false,
+            Some(ident) if &ident.to_string() == "reject" => true,
+            Some(ident) => {
+                throw!(ident, "Expected #[prop(on_invalid = clamp|reject)]");
+            }
+        };
+        if let (Some((key, _)), None) = (spec.key("on_invalid"), spec.value("range")) {
+            throw!(key, "#[prop(on_invalid = ..)] requires range");
+        }
+        // there is nothing to clamp to beyond the excluded end
+        if let (false, Some(Expr::Range(range))) = (reject, spec.value("range")) {
+            if matches!(range.limits, RangeLimits::HalfOpen(_)) && range.end.is_some() {
+                throw!(
+                    range,
+                    "Clamped #[prop(range = ..)] should include the end, use `..=` or `on_invalid = reject`"
+                );
+            }
+        }
+        Ok(PropMeta {
+            range: spec.value("range").cloned(),
+            step: spec.value("step").cloned(),
+            unit: spec.value("unit").cloned(),
+            reject,
+            tags: spec.values("tag").cloned().collect(),
+            group: spec.value("group").cloned(),
+        })
+    }
+
+    /// Keeps `__value__` in range before it is written, `return`s from the setter if
+    /// the value is rejected.
+    pub fn build_setter_constraint(&self, ctx: &Context) -> TokenStream {
+        let lib = ctx.constructivism();
+        match &self.range {
+            None => quote! {},
+            Some(range) if self.reject => quote! {
+                if !::std::ops::RangeBounds::contains(&(#range), &__value__) {
+                    return;
+                }
+            },
+            Some(range) => quote! {
+                let Some(__value__) = #lib::clamp_to_range(__value__, #range) else {
+                    return;
+                };
+            },
+        }
+    }
+
+    /// Keeps the param in range before the item is constructed. Rejected values fall
+    /// back to the default of the param. Required params without the default are
+    /// clamped, `construct!` panics if there is nothing in the range to clamp them to.
+    pub fn build_param_constraint(&self, ctx: &Context, this: &Type, param: &Param) -> TokenStream {
+        let lib = ctx.constructivism();
+        let ident = &param.name;
+        let Some(range) = &self.range else {
+            return quote! {};
+        };
+        let msg = format!(
+            "{}.{} is out of range {}",
+            this.to_token_stream(),
+            ident,
+            range.to_token_stream()
+        );
+        let clamp = quote! {
+            match #lib::clamp_to_range(#ident, #range) {
+                Some(__value__) => __value__,
+                None => panic!(#msg),
+            }
+        };
+        match (&param.reset, self.reject) {
+            (Some(default), true) => quote! {
+                if !::std::ops::RangeBounds::contains(&(#range), &#ident) {
+                    #ident = #default;
+                }
+            },
+            (Some(default), false) => quote! {
+                #ident = match #lib::clamp_to_range(#ident, #range) {
+                    Some(__value__) => __value__,
+                    None => #default,
+                };
+            },
+            (None, true) => quote! {
+                if !::std::ops::RangeBounds::contains(&(#range), &#ident) {
+                    #ident = #clamp;
+                }
+            },
+            (None, false) => quote! {
+                #ident = #clamp;
+            },
+        }
+    }
+
+    pub fn build_descriptor(&self, ctx: &Context, name: &Ident) -> TokenStream {
+        let lib = ctx.constructivism();
+        let name = name.to_string();
+        let range = match &self.range {
+            None => quote! { None },
+            Some(range) => quote! {
+                Some(#lib::PropRange::from_bounds(&(#range)))
+            },
+        };
+        let step = match &self.step {
+            None => quote! { None },
+            Some(step) => quote! { Some(#step) },
+        };
+        let unit = match &self.unit {
+            None => quote! { None },
+            Some(unit) => quote! { Some(#unit) },
+        };
+        let reject = self.reject;
+        let tags = self.build_tags();
+        let group = self.build_group();
+        quote! {
+            #lib::PropMeta {
+                name: #name,
+                range: #range,
+                step: #step,
+                unit: #unit,
+                reject: #reject,
+                tags: #tags,
+                group: #group,
+            }
+        }
+    }
+
+    pub fn build_tags(&self) -> TokenStream {
+        let tags = &self.tags;
+        quote! { &[ #( #tags ),* ] }
+    }
+
+    pub fn build_group(&self) -> TokenStream {
+        match &self.group {
+            None => quote! { None },
+            Some(group) => quote! { Some(#group) },
+        }
+    }
+}
+
 pub enum PropKind {
     Value,
     Construct,
     GetSet(Ident, Ident),
+    /// Read-only prop, `ratio: f32 = [ratio]` or `#[prop(get = ratio, ty = f32)]`
+    Get(Ident),
 }
 
 impl Parse for Prop {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let docs = Attribute::parse_outer(&input)?
+        let attrs = Attribute::parse_outer(&input)?;
+        let docs = attrs
             .iter()
             .filter(|a| a.path().is_ident("doc"))
             .cloned()
             .collect();
+        let spec = PropSpec::from_attrs(&attrs)?.unwrap_or_default();
+        if let Some(flag) = spec.flags.first() {
+            throw!(
+                flag,
+                "Unexpected #[prop] flag, use `= construct|value|[get, set]|[get]` instead"
+            );
+        }
+        let commit = spec.ident("commit")?;
+        let meta = PropMeta::from_spec(&spec)?;
         let ident = input.parse()?;
         input.parse::<Token![:]>()?;
         let ty = input.parse()?;
@@ -568,24 +1168,33 @@ impl Parse for Prop {
             } else if &ident.to_string() == "value" {
                 PropKind::Value
             } else {
-                throw!(ident, "Expected construct|value|[get,set].");
+                throw!(ident, "Expected construct|value|[get,set]|[get].");
             }
         } else {
             let content;
             bracketed!(content in input);
             let get = content.parse()?;
-            content.parse::<Token![,]>()?;
-            let set = content.parse()?;
-            if !content.is_empty() {
-                throw!(content, "Unexpected input for prop [get, set].");
+            if content.is_empty() {
+                PropKind::Get(get)
+            } else {
+                content.parse::<Token![,]>()?;
+                let set = content.parse()?;
+                if !content.is_empty() {
+                    throw!(content, "Unexpected input for prop [get, set].");
+                }
+                PropKind::GetSet(get, set)
             }
-            PropKind::GetSet(get, set)
         };
+        if let (PropKind::Get(get), Some(_)) = (&kind, &meta.range) {
+            throw!(get, "Read-only props can't have range.");
+        }
         Ok(Prop {
             docs,
             ident,
             kind,
             ty,
+            commit,
+            meta,
         })
     }
 }
@@ -602,38 +1211,67 @@ impl Prop {
             .filter(|a| a.path().is_ident("doc"))
             .cloned()
             .collect();
-        let mut attrs = field.attrs.iter().filter(|a| a.path().is_ident("prop"));
-        if let Some(attr) = attrs.next() {
-            let spec = attr.parse_args_with(PropSpec::parse)?;
+        let spec = PropSpec::from_attrs(&field.attrs)?.unwrap_or_default();
+        let commit = spec.ident("commit")?;
+        let meta = PropMeta::from_spec(&spec)?;
+        let kind = if !spec.flags.is_empty() {
             if spec.construct() {
-                Ok(Prop {
-                    ident,
-                    ty,
-                    docs,
-                    kind: PropKind::Construct,
-                })
+                PropKind::Construct
             } else {
                 let (get, set) = spec.getset()?;
-                Ok(Prop {
-                    ident,
-                    ty,
-                    docs,
-                    kind: PropKind::GetSet(get, set),
-                })
+                PropKind::GetSet(get, set)
             }
-        } else if let Some(ident) = field.ident.clone() {
-            Ok(Prop {
-                ty,
-                ident,
-                docs,
-                kind: PropKind::Value,
-            })
+        } else if field.ident.is_some() {
+            PropKind::Value
         } else {
             throw!(
                 field,
                 "#[param(get, set)] is required for unnamed struct fields"
             );
-        }
+        };
+        Ok(Prop {
+            ident,
+            ty,
+            docs,
+            kind,
+            commit,
+            meta,
+        })
+    }
+    /// Read-only prop declared on the struct: `#[prop(get = area, ty = f32)]`
+    pub fn from_struct_attr(attr: &Attribute) -> syn::Result<Self> {
+        attr.parse_args_with(|input: ParseStream| {
+            let mut get: Option<Ident> = None;
+            let mut ty: Option<Type> = None;
+            while !input.is_empty() {
+                let key = input.parse::<Ident>()?;
+                input.parse::<Token![=]>()?;
+                match key.to_string().as_str() {
+                    "get" => get = Some(input.parse()?),
+                    "ty" => ty = Some(input.parse()?),
+                    _ => {
+                        throw!(key, "Unexpected #[prop] key, expected get|ty");
+                    }
+                }
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            let Some(get) = get else {
+                throw!(attr, "Expected #[prop(get = method, ty = Type)]");
+            };
+            let Some(ty) = ty else {
+                throw!(attr, "Expected #[prop(get = method, ty = Type)]");
+            };
+            Ok(Prop {
+                ident: get.clone(),
+                ty,
+                kind: PropKind::Get(get),
+                commit: None,
+                meta: PropMeta::default(),
+                docs: vec![],
+            })
+        })
     }
     pub fn docs(&self) -> TokenStream {
         let mut out = quote! {};
@@ -662,7 +1300,7 @@ impl Prop {
                     )
                 }
             },
-            PropKind::GetSet(get, _set) => quote! {
+            PropKind::GetSet(get, _) | PropKind::Get(get) => quote! {
                 #docs
                 pub fn #ident(self) -> #lib::Value<'a, #ty> {
                     #lib::Value::Val(self.0.#get())
@@ -670,47 +1308,106 @@ impl Prop {
             },
         })
     }
-    pub fn build_setter(&self, ctx: &Context) -> syn::Result<TokenStream> {
+    pub fn build_setter(&self, ctx: &Context, this: &Type) -> syn::Result<TokenStream> {
         let lib = ctx.path("constructivism");
         let ty = &self.ty;
         let ident = &self.ident;
-        Ok(match &self.kind {
-            PropKind::Value => {
-                let setter = format_ident!("set_{}", ident);
-                quote! {
+        let setter = format_ident!("set_{}", ident);
+        let stage = format_ident!("stage_{}", ident);
+        let commit = format_ident!("commit_{}", ident);
+        let writer = format_ident!("write_{}", ident);
+        let write = match &self.kind {
+            PropKind::Value | PropKind::Construct => quote! { self.0.#ident = __value__; },
+            PropKind::GetSet(_get, set) => quote! { self.0.#set(__value__); },
+            // read-only props can't be written, there are no setters, `prop!` paths
+            // ending with them turn into `PropRef` by the type of the writer
+            PropKind::Get(_) => {
+                return Ok(quote! {
                     #[doc(hidden)]
-                    pub fn #setter(self, __value__: #ty) {
-                        self.0.#ident = __value__;
+                    pub fn #writer(self) -> #lib::ReadOnly {
+                        #lib::ReadOnly
                     }
-                }
+                })
             }
-            PropKind::Construct => {
-                let setter = format_ident!("set_{}", ident);
-                quote! {
-                    #[doc(hidden)]
-                    pub fn #ident(self) -> <#ty as #lib::ConstructItem>::Setters<'a> {
-                        <<#ty as #lib::ConstructItem>::Setters<'a> as #lib::Setters<'a, #ty>>::from_mut(
-                            &mut self.0.#ident
-                        )
-                    }
-                    #[doc(hidden)]
-                    pub fn #setter(self, __value__: #ty) {
-                        self.0.#ident = __value__;
-                    }
+        };
+        let constraint = self.meta.build_setter_constraint(ctx);
+        let write = quote! { #constraint #write };
+        let hook = match &self.commit {
+            Some(hook) => quote! { self.0.#hook(); },
+            None => quote! {},
+        };
+        // `prop!` paths stage through the field with it: `prop!(Node.position.0)`, the
+        // constrained and `[get, set]` values are written back, the hook is called by
+        // `commit_x` after
+        let field = format_ident!("with_{}", ident);
+        let field = match &self.kind {
+            PropKind::Value | PropKind::Construct if self.meta.range.is_none() => quote! {
+                #[doc(hidden)]
+                pub fn #field<R>(self, f: impl FnOnce(&mut #ty) -> R) -> R {
+                    f(&mut self.0.#ident)
                 }
-            }
-            PropKind::GetSet(_get, set) => {
-                let setter = format_ident!("set_{}", ident);
-                quote! {
-                    #[doc(hidden)]
-                    pub fn #ident(self, __value__: #ty) {
-                        self.0.#set(__value__);
-                    }
-                    #[doc(hidden)]
-                    pub fn #setter(self, __value__: #ty) {
-                        self.0.#set(__value__);
-                    }
+            },
+            PropKind::Value | PropKind::Construct => quote! {
+                #[doc(hidden)]
+                pub fn #field<R>(self, f: impl FnOnce(&mut #ty) -> R) -> R {
+                    let mut __value__ = ::std::clone::Clone::clone(&self.0.#ident);
+                    let __result__ = f(&mut __value__);
+                    let mut __write__ = |__value__: #ty| { #write };
+                    __write__(__value__);
+                    __result__
+                }
+            },
+            PropKind::GetSet(get, _) => quote! {
+                #[doc(hidden)]
+                pub fn #field<R>(self, f: impl FnOnce(&mut #ty) -> R) -> R {
+                    let mut __value__ = self.0.#get();
+                    let __result__ = f(&mut __value__);
+                    let mut __write__ = |__value__: #ty| { #write };
+                    __write__(__value__);
+                    __result__
+                }
+            },
+            PropKind::Get(_) => quote! {},
+        };
+        let access = match &self.kind {
+            PropKind::Value | PropKind::Get(_) => quote! {},
+            PropKind::Construct => quote! {
+                #[doc(hidden)]
+                pub fn #ident(self) -> <#ty as #lib::ConstructItem>::Setters<'a> {
+                    <<#ty as #lib::ConstructItem>::Setters<'a> as #lib::Setters<'a, #ty>>::from_mut(
+                        &mut self.0.#ident
+                    )
+                }
+            },
+            PropKind::GetSet(..) => quote! {
+                #[doc(hidden)]
+                pub fn #ident(self, __value__: #ty) {
+                    #write
+                    #hook
                 }
+            },
+        };
+        Ok(quote! {
+            #access
+            #field
+            #[doc(hidden)]
+            pub fn #writer(self) -> #lib::PropWrite<#this, #ty> {
+                <<#this as #lib::Construct>::Props<#lib::Lookup> as #lib::Singleton>::instance()
+                    .setters()
+                    .#writer()
+            }
+            #[doc(hidden)]
+            pub fn #setter(self, __value__: #ty) {
+                #write
+                #hook
+            }
+            #[doc(hidden)]
+            pub fn #stage(self, __value__: #ty) {
+                #write
+            }
+            #[doc(hidden)]
+            pub fn #commit(self) {
+                #hook
             }
         })
     }
@@ -739,7 +1436,7 @@ impl Prop {
                     }
                 }
             }
-            PropKind::GetSet(get, _set) => {
+            PropKind::GetSet(get, _) | PropKind::Get(get) => {
                 quote! {
                     #docs
                     pub fn #ident<'a>(&self, __this__: &'a #this) -> #lib::Value<'a, #ty> {
@@ -754,87 +1451,323 @@ impl Prop {
         let lib = ctx.constructivism();
         let ident = &self.ident;
         let ty = &self.ty;
-        Ok(match &self.kind {
-            PropKind::Value => {
-                let setter = format_ident!("set_{}", ident);
-                quote! {
-                    #[doc(hidden)]
-                    pub fn #ident(&self, __this__: &mut #this, __value__: #ty) {
-                        __this__.#ident = __value__;
-                    }
+        let writer = format_ident!("write_{}", ident);
+        let write = match &self.kind {
+            PropKind::Value | PropKind::Construct => quote! { __this__.#ident = __value__; },
+            PropKind::GetSet(_get, set) => quote! { __this__.#set(__value__); },
+            // `prop!` turns into `PropRef` by the type of the writer, see `build_prop`
+            PropKind::Get(_) => {
+                return Ok(quote! {
                     #[doc(hidden)]
-                    pub fn #setter(&self, __this__: &mut #this, __value__: #ty) {
-                        __this__.#ident = __value__;
+                    pub fn #writer(&self) -> #lib::ReadOnly {
+                        #lib::ReadOnly
                     }
-                }
+                })
             }
-            PropKind::Construct => {
-                let setter = format_ident!("set_{}", ident);
-                quote! {
-                    #[doc(hidden)]
-                    pub fn #ident<'a>(&self, __this__: &'a mut #this) -> <#ty as #lib::ConstructItem>::Setters<'a> {
-                        <<#ty as #lib::ConstructItem>::Setters<'a> as #lib::Setters<'a, #ty>>::from_mut(
-                            &mut __this__.#ident
-                        )
-                    }
-                    #[doc(hidden)]
-                    pub fn #setter(&self, __this__: &mut #this, __value__: #ty) {
-                        __this__.#ident = __value__;
-                    }
+        };
+        let constraint = self.meta.build_setter_constraint(ctx);
+        let write = quote! { #constraint #write };
+        let hook = match &self.commit {
+            Some(hook) => quote! { __this__.#hook(); },
+            None => quote! {},
+        };
+        // props sharing the hook are committed once by `set_many`
+        let hook_id = match &self.commit {
+            Some(hook) => quote! {
+                Some(#lib::PropId::new(
+                    concat!(module_path!(), "::", stringify!(#this), "::", stringify!(#hook))
+                ).as_u64())
+            },
+            None => quote! { None },
+        };
+        let restage = matches!(self.kind, PropKind::GetSet(..));
+        let field = format_ident!("with_{}", ident);
+        let field = match &self.kind {
+            PropKind::Value | PropKind::Construct if self.meta.range.is_none() => quote! {
+                #[doc(hidden)]
+                pub fn #field<R>(&self, __this__: &mut #this, f: impl FnOnce(&mut #ty) -> R) -> R {
+                    f(&mut __this__.#ident)
                 }
+            },
+            PropKind::Value | PropKind::Construct => quote! {
+                #[doc(hidden)]
+                pub fn #field<R>(&self, __this__: &mut #this, f: impl FnOnce(&mut #ty) -> R) -> R {
+                    let mut __value__ = ::std::clone::Clone::clone(&__this__.#ident);
+                    let __result__ = f(&mut __value__);
+                    let mut __write__ = |__value__: #ty| { #write };
+                    __write__(__value__);
+                    __result__
+                }
+            },
+            PropKind::GetSet(get, _) => quote! {
+                #[doc(hidden)]
+                pub fn #field<R>(&self, __this__: &mut #this, f: impl FnOnce(&mut #ty) -> R) -> R {
+                    let mut __value__ = __this__.#get();
+                    let __result__ = f(&mut __value__);
+                    let mut __write__ = |__value__: #ty| { #write };
+                    __write__(__value__);
+                    __result__
+                }
+            },
+            PropKind::Get(_) => quote! {},
+        };
+        let access = match &self.kind {
+            PropKind::Construct => quote! {
+                #[doc(hidden)]
+                pub fn #ident<'a>(&self, __this__: &'a mut #this) -> <#ty as #lib::ConstructItem>::Setters<'a> {
+                    <<#ty as #lib::ConstructItem>::Setters<'a> as #lib::Setters<'a, #ty>>::from_mut(
+                        &mut __this__.#ident
+                    )
+                }
+            },
+            PropKind::Value | PropKind::GetSet(..) | PropKind::Get(_) => quote! {
+                #[doc(hidden)]
+                pub fn #ident(&self, __this__: &mut #this, __value__: #ty) {
+                    #write
+                    #hook
+                }
+            },
+        };
+        Ok(quote! {
+            #access
+            #field
+            #[doc(hidden)]
+            pub fn #writer(&self) -> #lib::PropWrite<#this, #ty> {
+                #lib::PropWrite::new(
+                    |__this__: &mut #this, __value__: #ty| { #write #hook },
+                    |__this__: &mut #this, __value__: #ty| { #write },
+                    |__this__: &mut #this| { #hook },
+                    #lib::PropCommit { hook: #hook_id, restage: #restage },
+                )
             }
-            PropKind::GetSet(_get, set) => {
-                let setter = format_ident!("set_{}", ident);
+        })
+    }
+
+    /// Returns the `where` predicate and the body of the `Diff` implementation for this prop.
+    pub fn build_diff(
+        &self,
+        ctx: &Context,
+        this: &Type,
+    ) -> syn::Result<(TokenStream, TokenStream)> {
+        let lib = ctx.constructivism();
+        let ident = &self.ident;
+        let ty = &self.ty;
+        let name = ident.to_string();
+        Ok(match &self.kind {
+            PropKind::Construct => (
+                quote! { for<'__a> #ty: #lib::Diff + 'static, },
                 quote! {
-                    #[doc(hidden)]
-                    pub fn #ident(&self, __this__: &mut #this, __value__: #ty) {
-                        __this__.#set(__value__);
-                    }
-                    #[doc(hidden)]
-                    pub fn #setter(&self, __this__: &mut #this, __value__: #ty) {
-                        __this__.#set(__value__);
-                    }
-                }
+                    let mut __nested__ = vec![];
+                    <#ty as #lib::Diff>::diff(&__from__.#ident, &__to__.#ident, &mut __nested__);
+                    __changes__.extend(__nested__.into_iter().map(|__change__| {
+                        __change__.nest(#name, |__host__: &mut #this| &mut __host__.#ident)
+                    }));
+                },
+            ),
+            PropKind::Get(_) => (quote! {}, quote! {}),
+            PropKind::Value | PropKind::GetSet(..) => {
+                let setter = format_ident!("set_{}", ident);
+                (
+                    quote! { for<'__a> #ty: PartialEq + Clone + 'static, },
+                    quote! {
+                        let __a__ = Getters(__from__).#ident();
+                        let __b__ = Getters(__to__).#ident();
+                        if __a__.as_ref() != __b__.as_ref() {
+                            let __value__ = __b__.get();
+                            __changes__.push(#lib::Change::new(#name, move |__host__: &mut #this| {
+                                Setters(__host__).#setter(__value__.clone())
+                            }));
+                        }
+                    },
+                )
             }
         })
     }
 
-    pub fn build_type_descriptor(&self, _ctx: &Context, _this: &Type) -> syn::Result<TokenStream> {
+    /// Returns bounds, immutable and mutable visiting of the prop.
+    pub fn build_visit(&self, ctx: &Context) -> (TokenStream, TokenStream, TokenStream) {
+        let lib = ctx.constructivism();
         let ident = &self.ident;
+        let ty = &self.ty;
+        let name = ident.to_string();
+        match &self.kind {
+            PropKind::Construct => {
+                let commit = self.commit.as_ref().map(|c| quote! { self.#c(); });
+                (
+                    quote! { for<'__a> #ty: #lib::VisitProps + 'static, },
+                    quote! {
+                        if __visitor__.enter(#name) {
+                            #lib::VisitProps::visit_props(&self.#ident, __visitor__);
+                            __visitor__.exit(#name);
+                        } else {
+                            __visitor__.visit(#name, &self.#ident);
+                        }
+                    },
+                    quote! {
+                        if __visitor__.enter(#name) {
+                            #lib::VisitProps::visit_props_mut(&mut self.#ident, __visitor__);
+                            __visitor__.exit(#name);
+                        } else {
+                            __visitor__.visit(#name, &mut self.#ident);
+                        }
+                        #commit
+                    },
+                )
+            }
+            PropKind::Get(_) => (
+                quote! { for<'__a> #ty: 'static, },
+                quote! { __visitor__.visit(#name, Getters(self).#ident().as_ref()); },
+                quote! {},
+            ),
+            PropKind::Value if self.commit.is_none() && self.meta.range.is_none() => (
+                quote! { for<'__a> #ty: 'static, },
+                quote! { __visitor__.visit(#name, &self.#ident); },
+                quote! { __visitor__.visit(#name, &mut self.#ident); },
+            ),
+            PropKind::Value | PropKind::GetSet(..) => {
+                let setter = format_ident!("set_{}", ident);
+                (
+                    quote! { for<'__a> #ty: Clone + 'static, },
+                    quote! { __visitor__.visit(#name, Getters(self).#ident().as_ref()); },
+                    quote! {
+                        let mut __value__ = Getters(self).#ident().get();
+                        __visitor__.visit(#name, &mut __value__);
+                        Setters(self).#setter(__value__);
+                    },
+                )
+            }
+        }
+    }
+
+    pub fn build_type_descriptor(&self, ctx: &Context, _this: &Type) -> syn::Result<TokenStream> {
+        let lib = ctx.constructivism();
+        let ident = &self.ident;
+        let ty = &self.ty;
+        let meta = format_ident!("meta_{}", ident);
+        let descriptor = self.meta.build_descriptor(ctx, ident);
         Ok(quote! {
             pub fn #ident(&self) -> &'static TypeReference {
                 &TypeReference
             }
+            pub fn #meta(&self) -> #lib::PropMeta<#ty> {
+                #descriptor
+            }
         })
     }
 }
 
-pub struct PropSpec(pub Vec<Ident>);
+pub enum PropSpecItem {
+    Flag(Ident),
+    Value(Ident, Expr),
+}
+
+impl Parse for PropSpecItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `abstract` and `final` are reserved keywords
+        let ident = Ident::parse_any(input)?;
+        if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Ok(PropSpecItem::Value(ident, input.parse()?))
+        } else {
+            Ok(PropSpecItem::Flag(ident))
+        }
+    }
+}
+
+/// Content of the `#[prop(..)]` attributes: `#[prop(getter, setter, commit = normalize)]`
+#[derive(Default)]
+pub struct PropSpec {
+    pub flags: Vec<Ident>,
+    pub values: Vec<(Ident, Expr)>,
+}
 
 impl Parse for PropSpec {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        Ok(PropSpec(
-            input
-                .parse_terminated(Ident::parse, Token![,])?
-                .into_iter()
-                .collect(),
-        ))
+        let mut spec = PropSpec::default();
+        for item in input.parse_terminated(PropSpecItem::parse, Token![,])? {
+            match item {
+                PropSpecItem::Flag(ident) => spec.flags.push(ident),
+                PropSpecItem::Value(key, value) => {
+                    if !PropSpec::KEYS.contains(&key.to_string().as_str()) {
+                        throw!(
+                            key,
+                            "Unexpected #[prop] key, expected one of: {}",
+                            PropSpec::KEYS.join(", ")
+                        );
+                    }
+                    spec.values.push((key, value))
+                }
+            }
+        }
+        Ok(spec)
     }
 }
 impl PropSpec {
+    pub const KEYS: &'static [&'static str] = &[
+        "commit",
+        "range",
+        "step",
+        "unit",
+        "on_invalid",
+        "tag",
+        "group",
+    ];
+
+    /// Merges all `#[prop(..)]` attributes into the single spec.
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Option<Self>> {
+        let mut out: Option<PropSpec> = None;
+        for attr in attrs.iter().filter(|a| a.path().is_ident("prop")) {
+            let spec = attr.parse_args_with(PropSpec::parse)?;
+            let out = out.get_or_insert_with(PropSpec::default);
+            out.flags.extend(spec.flags);
+            out.values.extend(spec.values);
+        }
+        Ok(out)
+    }
     pub fn skip(&self) -> bool {
-        self.0.len() == 1 && &self.0[0].to_string() == "skip"
+        self.flags.len() == 1 && &self.flags[0].to_string() == "skip"
     }
     pub fn construct(&self) -> bool {
-        self.0.len() == 1 && &self.0[0].to_string() == "construct"
+        self.flags.len() == 1 && &self.flags[0].to_string() == "construct"
     }
     pub fn getset(&self) -> syn::Result<(Ident, Ident)> {
-        if self.0.len() == 2 {
-            Ok((self.0[0].clone(), self.0[1].clone()))
+        if self.flags.len() == 2 {
+            Ok((self.flags[0].clone(), self.flags[1].clone()))
+        } else if let Some(flag) = self.flags.first() {
+            throw!(flag, "Expected #[prop(getter, setter)]");
         } else {
-            throw!(self.0[0], "Expected #[prop(getter, setter)]");
+            throw!(Span::call_site(), "Expected #[prop(getter, setter)]");
         }
     }
+    pub fn value(&self, key: &str) -> Option<&Expr> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+    /// The last `key = value` pair, for errors spanned on the key.
+    pub fn key(&self, key: &str) -> Option<&(Ident, Expr)> {
+        self.values.iter().rev().find(|(k, _)| k == key)
+    }
+    pub fn values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Expr> {
+        self.values
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+    pub fn ident(&self, key: &str) -> syn::Result<Option<Ident>> {
+        let Some(value) = self.value(key) else {
+            return Ok(None);
+        };
+        let Expr::Path(path) = value else {
+            throw!(value, "Expected identifier for #[prop({} = ..)]", key);
+        };
+        let Some(ident) = path.path.get_ident() else {
+            throw!(value, "Expected identifier for #[prop({} = ..)]", key);
+        };
+        Ok(Some(ident.clone()))
+    }
 }
 
 #[derive(Default)]
@@ -855,13 +1788,8 @@ impl Props {
     pub fn from_fields(fields: &Fields) -> syn::Result<Self> {
         let mut props = vec![];
         for field in fields.iter() {
-            if let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("prop")) {
-                // attr.meta.require_list()?.
-                // attr.meta.to_token_stream()
-                // Punctuated::<Ident, Token![,]>::parse_terminated(attr.meta.to_token_stream())?;
-                // attr.parse_args::<Punctuated<Ident, Token![,]>>()?;
-                let skip = attr.parse_args_with(PropSpec::parse)?.skip();
-                if skip {
+            if let Some(spec) = PropSpec::from_attrs(&field.attrs)? {
+                if spec.skip() {
                     continue;
                 }
             }
@@ -869,6 +1797,13 @@ impl Props {
         }
         Ok(Props(props))
     }
+    pub fn from_derive(attrs: &[Attribute], fields: &Fields) -> syn::Result<Self> {
+        let mut props = Props::from_fields(fields)?;
+        for attr in attrs.iter().filter(|a| a.path().is_ident("prop")) {
+            props.push(Prop::from_struct_attr(attr)?);
+        }
+        Ok(props)
+    }
     pub fn build_getters(&self, ctx: &Context) -> syn::Result<TokenStream> {
         let mut out = quote! {};
         for prop in self.iter() {
@@ -877,10 +1812,10 @@ impl Props {
         }
         Ok(out)
     }
-    pub fn build_setters(&self, ctx: &Context) -> syn::Result<TokenStream> {
+    pub fn build_setters(&self, ctx: &Context, this: &Type) -> syn::Result<TokenStream> {
         let mut out = quote! {};
         for prop in self.iter() {
-            let setter = prop.build_setter(ctx)?;
+            let setter = prop.build_setter(ctx, this)?;
             out = quote! { #out #setter }
         }
         Ok(out)
@@ -904,6 +1839,202 @@ impl Props {
         Ok(out)
     }
 
+    pub fn build_diff(&self, ctx: &Context, this: &Type) -> syn::Result<TokenStream> {
+        let lib = ctx.constructivism();
+        let mut bounds = quote! {};
+        let mut body = quote! {};
+        for prop in self.iter() {
+            let (bound, diff) = prop.build_diff(ctx, this)?;
+            bounds = quote! { #bounds #bound };
+            body = quote! { #body { #diff } };
+        }
+        Ok(quote! {
+            impl #lib::Diff for #this where #bounds {
+                fn diff(__from__: &Self, __to__: &Self, __changes__: &mut Vec<#lib::Change<Self>>) {
+                    #body
+                }
+            }
+        })
+    }
+
+    /// `VisitProps` of the item, see `visit_props()`.
+    pub fn build_visit(&self, ctx: &Context, this: &Type) -> TokenStream {
+        let lib = ctx.constructivism();
+        let host = this.to_token_stream().to_string();
+        let mut bounds = quote! {};
+        let mut visit = quote! {};
+        let mut visit_mut = quote! {};
+        for prop in self.iter() {
+            let (bound, value, value_mut) = prop.build_visit(ctx);
+            bounds = quote! { #bounds #bound };
+            visit = quote! { #visit { #value } };
+            visit_mut = quote! { #visit_mut { #value_mut } };
+        }
+        quote! {
+            impl #lib::VisitProps for #this where #bounds {
+                const HOST: &'static str = #host;
+                fn visit_props<V: #lib::PropVisitor>(&self, __visitor__: &mut V) {
+                    #visit
+                }
+                fn visit_props_mut<V: #lib::PropVisitorMut>(&mut self, __visitor__: &mut V) {
+                    #visit_mut
+                }
+            }
+        }
+    }
+
+    /// `Debug` from the props of the item, `#[construct(debug)]`.
+    pub fn build_debug(&self, ctx: &Context, this: &Type) -> syn::Result<TokenStream> {
+        let lib = ctx.constructivism();
+        let name = this.to_token_stream().to_string();
+        let mut bounds = quote! {};
+        let mut fields = quote! {};
+        for prop in self.iter() {
+            let ident = &prop.ident;
+            let ty = &prop.ty;
+            let field = ident.to_string();
+            let value = match &prop.kind {
+                PropKind::Construct => quote! {
+                    &<<#ty as #lib::ConstructItem>::Getters<'_> as #lib::Getters<'_, #ty>>::into_value(
+                        Getters(self).#ident()
+                    ).as_ref()
+                },
+                _ => quote! { &Getters(self).#ident().as_ref() },
+            };
+            bounds = quote! { #bounds for<'__a> #ty: ::std::fmt::Debug, };
+            fields = quote! { #fields .field(#field, #value) };
+        }
+        Ok(quote! {
+            impl ::std::fmt::Debug for #this where #bounds {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.debug_struct(#name) #fields .finish()
+                }
+            }
+        })
+    }
+
+    /// Links params to the props with the same name and type for `update!`. Custom
+    /// constructors are `Rebuild` only when every param has such prop and `rebuild`
+    /// is allowed.
+    pub fn build_update(
+        &self,
+        ctx: &Context,
+        this: &Type,
+        params: &[Param],
+        custom: bool,
+        rebuild: bool,
+    ) -> syn::Result<TokenStream> {
+        let lib = ctx.constructivism();
+        let mut out = quote! {};
+        let mut bounds = quote! {};
+        let mut values = Some(quote! {});
+        for param in params.iter().filter(|p| p.skip().is_none()) {
+            let ident = &param.name;
+            let ParamType::Single(param_ty) = &param.ty else {
+                throw!(ident, "Union params not supported yet.");
+            };
+            let Some(prop) = self.iter().find(|p| {
+                !matches!(p.kind, PropKind::Get(_))
+                    && &p.ident == ident
+                    && p.ty.to_token_stream().to_string() == param_ty.to_token_stream().to_string()
+            }) else {
+                values = None;
+                continue;
+            };
+            let ty = &prop.ty;
+            let setter = format_ident!("stage_{}", ident);
+            let commit = format_ident!("commit_{}", ident);
+            out = quote! { #out
+                impl #lib::ParamProp for #ident {
+                    type Host = #this;
+                    type Value = #ty;
+                    fn stage(__host__: &mut #this, __value__: #ty) {
+                        Setters(__host__).#setter(__value__)
+                    }
+                    fn commit(__host__: &mut #this) {
+                        Setters(__host__).#commit()
+                    }
+                }
+            };
+            let value = match &prop.kind {
+                PropKind::Construct => quote! {
+                    <<#ty as #lib::ConstructItem>::Getters<'_> as #lib::Getters<'_, #ty>>::into_value(
+                        Getters(self).#ident()
+                    ).get()
+                },
+                PropKind::Value | PropKind::GetSet(..) | PropKind::Get(_) => {
+                    quote! { Getters(self).#ident().get() }
+                }
+            };
+            bounds = quote! { #bounds for<'__a> #ty: Clone, };
+            values = values.map(|values| quote! { #values #ident(#value), });
+        }
+        let rebuild = match (custom, values) {
+            (false, _) => quote! {
+                impl #lib::Rebuild for #this {
+                    fn rebuild(&mut self) {}
+                }
+            },
+            (true, Some(values)) if rebuild => quote! {
+                impl #lib::Rebuild for #this where #bounds {
+                    fn rebuild(&mut self) {
+                        let __params__ = (#values);
+                        *self = <Self as #lib::ConstructItem>::construct_item(__params__);
+                    }
+                }
+            },
+            (true, _) => quote! {},
+        };
+        Ok(quote! { #out #rebuild })
+    }
+
+    /// `ConstructItem::PROPS`, used by `Construct::props_tagged()`.
+    pub fn build_infos(&self, ctx: &Context, this: &Type) -> TokenStream {
+        let lib = ctx.constructivism();
+        let host = this.to_token_stream().to_string();
+        let infos = self.iter().map(|prop| {
+            let name = prop.ident.to_string();
+            let tags = prop.meta.build_tags();
+            let group = prop.meta.build_group();
+            quote! {
+                #lib::PropInfo {
+                    host: #host,
+                    name: #name,
+                    tags: #tags,
+                    group: #group,
+                }
+            }
+        });
+        quote! {
+            const PROPS: &'static [#lib::PropInfo] = &[ #( #infos ),* ];
+        }
+    }
+
+    /// Keeps the params in the range of the props with the same name and type.
+    pub fn build_param_constraints(
+        &self,
+        ctx: &Context,
+        this: &Type,
+        params: &[Param],
+    ) -> syn::Result<TokenStream> {
+        let mut out = quote! {};
+        for param in params.iter().filter(|p| p.skip().is_none()) {
+            let ident = &param.name;
+            let ParamType::Single(param_ty) = &param.ty else {
+                continue;
+            };
+            let Some(prop) = self.iter().find(|p| {
+                &p.ident == ident
+                    && p.ty.to_token_stream().to_string() == param_ty.to_token_stream().to_string()
+            }) else {
+                continue;
+            };
+            let constraint = prop.meta.build_param_constraint(ctx, this, param);
+            out = quote! { #out #constraint };
+        }
+        Ok(out)
+    }
+
     pub fn build_type_descriptors(&self, ctx: &Context, this: &Type) -> syn::Result<TokenStream> {
         let mut out = quote! {};
         for prop in self.iter() {
@@ -927,6 +2058,7 @@ impl std::ops::DerefMut for Props {
 
 pub struct Constructor {
     params: Vec<Param>,
+    with: Vec<BaseRef>,
     expr: Expr,
 }
 
@@ -936,9 +2068,47 @@ impl Parse for Constructor {
         parenthesized!(content in input);
         let params = content.parse_terminated(Param::parse, Token![,])?;
         let params = params.into_iter().collect();
+        let mut with = vec![];
+        if input.peek(Ident) {
+            let ident = input.parse::<Ident>()?;
+            if ident != "with" {
+                throw!(ident, "Expected `with (base: &Base)` or `->`");
+            }
+            let content;
+            parenthesized!(content in input);
+            let refs = content.parse_terminated(BaseRef::parse, Token![,])?;
+            with = refs.into_iter().collect();
+        }
         input.parse::<Token![->]>()?;
         let expr = input.parse()?;
-        Ok(Constructor { params, expr })
+        Ok(Constructor { params, with, expr })
+    }
+}
+
+/// Already constructed base level passed to the constructor: `with (rect: &Rect)`
+pub struct BaseRef {
+    pub ident: Ident,
+    pub ty: Type,
+}
+
+impl Parse for BaseRef {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: Ident = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let Type::Reference(reference) = input.parse::<Type>()? else {
+            throw!(
+                ident,
+                "Expected reference to the base level: `{}: &Base`",
+                ident
+            );
+        };
+        if let Some(mutability) = reference.mutability {
+            throw!(mutability, "Base levels are passed by shared reference.");
+        }
+        Ok(BaseRef {
+            ident,
+            ty: *reference.elem,
+        })
     }
 }
 
@@ -948,6 +2118,9 @@ pub struct DeriveConstruct {
     pub params: Vec<Param>,
     pub props: Props,
     pub body: Option<Expr>,
+    /// Base levels the constructor reads: `with (rect: &Rect)`
+    pub with: Vec<BaseRef>,
+    pub flags: ConstructFlags,
 }
 
 impl Parse for DeriveConstruct {
@@ -957,14 +2130,17 @@ impl Parse for DeriveConstruct {
         let ty = sequence.this.clone();
         let constructor: Constructor = decls.parse_declaration("construct")?;
         let params = constructor.params;
+        let with = constructor.with;
         let body = Some(constructor.expr);
         let props = decls.parse_or_default("props")?;
         Ok(DeriveConstruct {
             ty,
             params,
             body,
+            with,
             sequence,
             props,
+            flags: ConstructFlags::default(),
         })
     }
 }
@@ -980,11 +2156,13 @@ impl DeriveConstruct {
         let ident = input.ident.clone(); // Slider
         let ty = syn::parse2(quote! { #ident }).unwrap();
         let sequence = Sequence::from_derive(&input)?;
+        let attrs = input.attrs.clone();
         let Data::Struct(input) = input.data else {
             throw!(input.ident, "#[derive(Construct)] only supports named structs. You can use `derive_construct!` for complex cases.");
         };
         let params = Params::from_fields(&input.fields, "Construct", "derive_construct")?;
-        let props = Props::from_fields(&input.fields)?;
+        let props = Props::from_derive(&attrs, &input.fields)?;
+        let flags = ConstructFlags::from_attrs(&attrs, true)?;
         let body = None;
         Ok(DeriveConstruct {
             ty,
@@ -992,6 +2170,8 @@ impl DeriveConstruct {
             params,
             props,
             body,
+            with: vec![],
+            flags,
         })
     }
 
@@ -1003,9 +2183,13 @@ impl DeriveConstruct {
         ))
     }
 
-    pub fn design_ident(&self) -> syn::Result<Ident> {
+    pub fn design_ident(&self) -> syn::Result<Type> {
+        if let Some(design) = &self.flags.design {
+            return Ok(design.clone());
+        }
         let type_ident = self.ty.as_ident()?;
-        Ok(format_ident!("{}Design", type_ident.to_string()))
+        let ident = format_ident!("{}Design", type_ident.to_string());
+        Ok(parse_quote! { #ident })
     }
 
     pub fn build(&self, ctx: &Context) -> syn::Result<TokenStream> {
@@ -1014,7 +2198,34 @@ impl DeriveConstruct {
         let type_ident = ty.as_ident()?;
         let mod_ident = self.mod_ident()?;
         let design = self.design_ident()?;
+        let decl_design = if let Some(design) = &self.flags.design {
+            quote! {
+                impl #lib::Singleton for #design {
+                    fn instance() -> &'static Self {
+                        static INSTANCE: ::std::sync::OnceLock<#design> = ::std::sync::OnceLock::new();
+                        INSTANCE.get_or_init(<#design as ::std::default::Default>::default)
+                    }
+                }
+            }
+        } else {
+            quote! {
+                pub struct #design;
+                impl #lib::Singleton for #design {
+                    fn instance() -> &'static Self {
+                        &#design
+                    }
+                }
+            }
+        };
         let mut deref_design;
+        let builded = self.params.build(ctx, &ty, &mod_ident)?;
+        // constructors reading the base levels can't be called without them
+        let rebuild = self.with.is_empty();
+        let reset = if rebuild {
+            builded.build_reset(ctx, ty, self.body.is_some())
+        } else {
+            None
+        };
         let BuildedParams {
             fields,
             fields_new,
@@ -1022,12 +2233,27 @@ impl DeriveConstruct {
             param_values,
             type_params,
             type_params_deconstruct,
-        } = self.params.build(ctx, &ty, &mod_ident)?;
+            ..
+        } = builded;
         let props_getters = self.props.build_lookup_getters(ctx, &ty)?;
         let props_setters = self.props.build_lookup_setters(ctx, &ty)?;
         let props_descriptors = self.props.build_type_descriptors(ctx, &ty)?;
         let getters = self.props.build_getters(ctx)?;
-        let setters = self.props.build_setters(ctx)?;
+        let setters = self.props.build_setters(ctx, ty)?;
+        let diff = self.props.build_diff(ctx, ty)?;
+        let visit = self.props.build_visit(ctx, ty);
+        let debug = if self.flags.debug {
+            self.props.build_debug(ctx, ty)?
+        } else {
+            quote! {}
+        };
+        let update =
+            self.props
+                .build_update(ctx, ty, &self.params, self.body.is_some(), rebuild)?;
+        let constraints = self.props.build_param_constraints(ctx, ty, &self.params)?;
+        let infos = self.props.build_infos(ctx, ty);
+        let provide = self.flags.build_provide(ctx);
+        let after = self.flags.build_after();
         let decls = {
             let base = &self.sequence.next;
             let base = if !base.is_nothing() {
@@ -1038,10 +2264,20 @@ impl DeriveConstruct {
             let mut deref_fields = quote! { <#base as #lib::Construct>::Params };
             let mut deref_props = quote! { <#base as #lib::Construct>::Props<M> };
             deref_design = quote! { <#base as #lib::Construct>::Design };
-            for segment in self.sequence.segments.iter() {
-                deref_fields = quote! { <#segment as #lib::Segment>::Params<#deref_fields> };
+            for SequenceSegment {
+                ty: segment,
+                optional,
+            } in self.sequence.segments.iter()
+            {
                 deref_design = quote! { <#segment as #lib::Segment>::Design<#deref_design> };
-                deref_props = quote! { <#segment as #lib::Segment>::Props<M, #deref_props> };
+                // the level of the optional segment may be missing, so its props are skipped
+                if *optional {
+                    deref_fields =
+                        quote! { <#segment as #lib::Segment>::OptionalFields<#deref_fields> };
+                } else {
+                    deref_fields = quote! { <#segment as #lib::Segment>::Params<#deref_fields> };
+                    deref_props = quote! { <#segment as #lib::Segment>::Props<M, #deref_props> };
+                }
             }
 
             quote! {
@@ -1125,6 +2361,10 @@ impl DeriveConstruct {
                     }
                 }
                 impl<M: 'static> #lib::Props<M> for Props<M> { }
+                #diff
+                #visit
+                #debug
+                #update
             }
         };
         let derive = {
@@ -1147,10 +2387,15 @@ impl DeriveConstruct {
             let mut expanded_params = quote! { <Self::Base as #lib::Construct>::ExpandedParams };
             let mut base_sequence = quote! { <Self::Base as #lib::Construct>::NestedSequence };
             let mut deconstruct = quote! {};
-            let mut construct = quote! { <Self::Base as #lib::Construct>::construct(rest) };
+            let mut construct = quote! { base };
+            // paths to the levels constructed by this construct: seq.0, seq.1.0, ..
+            let mut levels = vec![quote! { 0 }];
             for segment in self.sequence.segments.iter().rev() {
-                let segment_params =
-                    format_ident!("{}_params", segment.as_ident()?.to_string().to_lowercase());
+                let segment_params = format_ident!(
+                    "{}_params",
+                    segment.ty.as_ident()?.to_string().to_lowercase()
+                );
+                let segment = segment.item();
                 if mixed_params.is_empty() {
                     mixed_params = quote! { <#segment as #lib::ConstructItem>::Params, };
                     deconstruct = quote! { #segment_params };
@@ -1161,6 +2406,8 @@ impl DeriveConstruct {
                 expanded_params = quote! { #lib::Mix<<#segment as #lib::ConstructItem>::Params, #expanded_params> };
                 construct = quote! { ( <#segment as #lib::ConstructItem>::construct_item(#segment_params), #construct ) };
                 base_sequence = quote! { (#segment, #base_sequence) };
+                let last = levels.last().unwrap().clone();
+                levels.push(quote! { 1.#last });
             }
             let mixed_params = if mixed_params.is_empty() {
                 quote! { (#type_params) }
@@ -1172,13 +2419,111 @@ impl DeriveConstruct {
             } else {
                 quote! { (self_params, #deconstruct) }
             };
-            let construct = quote! {
-                (
-                    <Self as #lib::ConstructItem>::construct_item(self_params),
-                    #construct
-                )
+            let construct = if self.with.is_empty() {
+                quote! {
+                    (
+                        <Self as #lib::ConstructItem>::construct_item(self_params),
+                        #construct
+                    )
+                }
+            } else {
+                let body = &self.body;
+                let mut refs = quote! {};
+                for BaseRef { ident, ty } in self.with.iter() {
+                    refs = quote! { #refs
+                        let #ident: &#ty = #lib::Find::<#ty, _>::find(&__base__);
+                    };
+                }
+                quote! {{
+                    let __base__ = #construct;
+                    let __this__ = {
+                        #refs
+                        let (#type_params_deconstruct) = self_params;
+                        #constraints
+                        #body
+                    };
+                    (__this__, __base__)
+                }}
+            };
+            if self.flags.is_abstract && self.flags.is_final {
+                throw!(
+                    this,
+                    "#[construct(abstract)] can't be combined with #[construct(final)]"
+                );
+            }
+            let instantiable = if self.flags.is_abstract {
+                quote! {}
+            } else {
+                quote! { impl #lib::Instantiable for #type_ident { } }
+            };
+            let extendable = if self.flags.is_final {
+                quote! {}
+            } else {
+                quote! { impl #lib::Extendable for #type_ident { } }
+            };
+            let placeable = self.sequence.segments.iter().map(|segment| {
+                let optional = if segment.optional {
+                    let segment = &segment.ty;
+                    quote! { const _: fn() = || #lib::assert_optional::<#segment>(); }
+                } else {
+                    quote! {}
+                };
+                let segment = &segment.ty;
+                quote! {
+                    const _: fn() = || #lib::assert_placeable::<#segment, #base>();
+                    #optional
+                }
+            });
+            let after = if self.flags.top_down {
+                quote! { after_top_down }
+            } else {
+                quote! { after_bottom_up }
+            };
+            let top_down = self.flags.top_down;
+            if !self.flags.top_down {
+                levels.reverse();
+            }
+            let after_levels = levels
+                .iter()
+                .map(|level| quote! { #lib::ConstructItem::after_construct(&mut seq.#level); });
+            let finalize = if let Some(finalize) = &self.flags.finalize {
+                quote! {
+                    let mut flat = #lib::Flattern::flattern(seq);
+                    #finalize(&mut flat);
+                    let seq = <Self::NestedSequence as #lib::Unflattern>::unflattern(flat);
+                }
+            } else {
+                quote! {}
+            };
+            // (__this__, (__level_1__, __base__)), the pattern and the expression
+            let mut split = quote! { __base__ };
+            for idx in (1..levels.len()).rev() {
+                let level = format_ident!("__level_{}__", idx);
+                split = quote! { (#level, #split) };
+            }
+            let split = quote! { (__this__, #split) };
+            let finalize_base = quote! {
+                let #split = seq;
+                let __base__ = <Self::Base as #lib::Construct>::finalize_sequence(__base__, top_down);
+                let seq = #split;
+            };
+            let finalize_sequence = if self.flags.finalize.is_some() {
+                quote! {
+                    let seq = if top_down { #finalize seq } else { seq };
+                    #finalize_base
+                    if top_down { seq } else { #finalize seq }
+                }
+            } else {
+                quote! {
+                    #finalize_base
+                    seq
+                }
             };
             quote! {
+                #instantiable
+                #extendable
+                const _: fn() = || #lib::assert_extendable::<#base>();
+                #(#placeable)*
                 impl #lib::Construct for #type_ident {
                     type Sequence = <Self::NestedSequence as #lib::Flattern>::Output;
                     type Base = #base;
@@ -1193,11 +2538,37 @@ impl DeriveConstruct {
                         I, Self::MixedParams,
                         Value = <Self::MixedParams as #lib::Extractable>::Output,
                         Rest = <<<Self::Base as #lib::Construct>::ExpandedParams as #lib::Extractable>::Input as #lib::AsParams>::Defined
+                    > {
+                        let mut seq = <Self as #lib::Construct>::construct_sequence(params);
+                        #lib::AfterSequence::#after(&mut seq);
+                        <Self as #lib::Construct>::finalize_sequence(seq, #top_down)
+                    }
+
+                    fn finalize_sequence(seq: Self::NestedSequence, top_down: bool) -> Self::NestedSequence {
+                        #finalize_sequence
+                    }
+
+                    fn construct_sequence<P, const I: u8>(params: P) -> Self::NestedSequence where P: #lib::ExtractParams<
+                        I, Self::MixedParams,
+                        Value = <Self::MixedParams as #lib::Extractable>::Output,
+                        Rest = <<<Self::Base as #lib::Construct>::ExpandedParams as #lib::Extractable>::Input as #lib::AsParams>::Defined
                     > {
                         let _: Option<#this> = None;
                         let (#deconstruct, rest) = params.extract_params();
+                        let base = <Self::Base as #lib::Construct>::construct_sequence(rest);
                         #construct
                     }
+
+                    fn construct_on(
+                        params: <Self::MixedParams as #lib::Extractable>::Output,
+                        base: <Self::Base as #lib::Construct>::NestedSequence,
+                    ) -> Self::NestedSequence {
+                        let #deconstruct = params;
+                        let mut seq = #construct;
+                        #( #after_levels )*
+                        #finalize
+                        seq
+                    }
                 }
             }
         };
@@ -1209,6 +2580,27 @@ impl DeriveConstruct {
             })
             .unwrap()
         };
+        // constructs reading the base levels are not `Standalone`, so `construct_item`
+        // can't be called for them
+        let (construct_item, standalone) = if self.with.is_empty() {
+            (
+                quote! {
+                    let (#type_params_deconstruct) = params;
+                    #constraints
+                    #construct
+                },
+                quote! { impl #lib::Standalone for #type_ident { } },
+            )
+        } else {
+            let name = type_ident.to_string();
+            (
+                quote! {
+                    let _ = params;
+                    unreachable!("{} is not Standalone", #name)
+                },
+                quote! {},
+            )
+        };
         Ok(quote! {
             mod #mod_ident {
                 use super::*;
@@ -1219,17 +2611,25 @@ impl DeriveConstruct {
                 type Params = ( #type_params );
                 type Getters<'a> = #mod_ident::Getters<'a>;
                 type Setters<'a> = #mod_ident::Setters<'a>;
+                #infos
+                #provide
+                #after
                 fn construct_item(params: Self::Params) -> Self {
-                    let (#type_params_deconstruct) = params;
-                    #construct
+                    #construct_item
                 }
             }
-            pub struct #design;
-                impl #lib::Singleton for #design {
-                    fn instance() -> &'static Self {
-                        &#design
-                    }
+            #standalone
+            #reset
+            impl #lib::Through for #type_ident {
+                type Target = Self;
+                fn through<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+                    f(self)
+                }
+                fn through_mut<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+                    f(self)
                 }
+            }
+            #decl_design
                 impl ::std::ops::Deref for #design {
                     type Target = #deref_design;
                     fn deref(&self) -> &Self::Target {
@@ -1240,3 +2640,41 @@ impl DeriveConstruct {
         })
     }
 }
+
+/// `#[virtual_design(Rect)] pub trait Expand { .. }` attaches the design trait to
+/// the `Rect` level, descendants override it with `#[construct(overrides = Expand)]`.
+pub struct VirtualDesign {
+    pub base: Type,
+    pub item: syn::ItemTrait,
+}
+
+impl VirtualDesign {
+    pub fn from_attr(attr: TokenStream, item: TokenStream) -> syn::Result<Self> {
+        Ok(VirtualDesign {
+            base: parse2(attr)?,
+            item: parse2(item)?,
+        })
+    }
+
+    pub fn build(&self, ctx: &Context) -> syn::Result<TokenStream> {
+        let lib = ctx.constructivism();
+        let item = &self.item;
+        let ident = &item.ident;
+        let base = &self.base;
+        if !item.generics.params.is_empty() {
+            throw!(
+                item.generics,
+                "#[virtual_design] doesn't support generics yet."
+            );
+        }
+        Ok(quote! {
+            #item
+            impl #lib::VirtualDesign for dyn #ident {
+                type Base = #base;
+                fn base() -> &'static Self {
+                    <<#base as #lib::Construct>::Design as #lib::Singleton>::instance()
+                }
+            }
+        })
+    }
+}
@@ -19,22 +19,32 @@ impl Parse for ConstructivistLimits {
 
 pub fn implement_constructivism_core(max_size: u8) -> TokenStream {
     let extract_field_impls = impl_all_extract_field(max_size);
+    let own_params = impl_all_own_params(max_size);
     let add_to_params = impl_all_add_to_params(max_size);
     let defined = impl_all_defined(max_size);
     let extracts = impl_all_extracts(max_size);
     let mixed = impl_all_mixed(max_size);
     let as_params = impl_all_as_params(max_size);
     let flattern = impl_all_flattern(max_size);
+    let unflattern = impl_all_unflattern(max_size);
     let contains = impl_all_contains(16);
+    let lerp = impl_all_lerp(max_size);
+    let tuples = impl_all_tuple_items(max_size);
+    let optional = impl_all_optional_params(max_size);
     quote! {
         #extract_field_impls
+        #own_params
         #add_to_params
         #defined
         #extracts
         #as_params
         #mixed
         #flattern
+        #unflattern
         #contains
+        #lerp
+        #tuples
+        #optional
     }
 }
 
@@ -64,6 +74,24 @@ fn impl_all_extract_field(max_size: u8) -> TokenStream {
     }
     out
 }
+fn impl_all_own_params(max_size: u8) -> TokenStream {
+    let mut out = quote! {};
+    for size in 1..max_size + 1 {
+        let ts = (0..size).map(|i| format_ident!("T{i}")).collect::<Vec<_>>();
+        let us = (0..size).map(|i| {
+            let ti = &ts[i as usize];
+            quote! { U<#i, #ti> }
+        });
+        let params = quote! { Params<(#(#us,)*)> };
+        for idx in 0..size {
+            let ti = &ts[idx as usize];
+            out = quote! { #out
+                impl<#(#ts,)*> OwnParam<#ti, F<#idx, #ti>> for #params {}
+            }
+        }
+    }
+    out
+}
 fn impl_all_add_to_params(max_size: u8) -> TokenStream {
     let mut out = quote! {};
     for size in 1..max_size + 1 {
@@ -184,6 +212,73 @@ fn impl_all_flattern(max_depth: u8) -> TokenStream {
     out
 }
 
+/// ```ignore
+/// impl<T0: ConstructItem, T1: ConstructItem> Unflattern for (T0, (T1, ())) {
+///     fn unflattern(flat: (T0, T1)) -> Self {
+///         let (p0, p1) = flat;
+///         (p0, (p1, ()))
+///     }
+///     fn unflattern_ref<'a>(flat: &'a (T0, T1)) -> (&'a T0, (&'a T1, ())) where Self: 'a {
+///         let (p0, p1) = flat;
+///         (p0, (p1, ()))
+///     }
+///     fn unflattern_mut<'a>(flat: &'a mut (T0, T1)) -> (&'a mut T0, (&'a mut T1, ())) where Self: 'a {
+///         let (p0, p1) = flat;
+///         (p0, (p1, ()))
+///     }
+/// }
+/// ```
+fn impl_all_unflattern(max_depth: u8) -> TokenStream {
+    let mut out = quote! {};
+    for depth in 1..max_depth + 1 {
+        let mut cstr = quote! {};
+        let mut ts = quote! {};
+        let mut vs = quote! {};
+        let mut ns = quote! { () };
+        let mut refs = quote! { () };
+        let mut muts = quote! { () };
+        let mut nested = quote! { () };
+        for i in 0..depth {
+            let ti = format_ident!("T{i}");
+            let pi = format_ident!("p{i}");
+            let tr = format_ident!("T{}", depth - i - 1);
+            let pr = format_ident!("p{}", depth - i - 1);
+            cstr = quote! { #cstr #ti: ConstructItem, };
+            ts = if i < depth - 1 {
+                quote! { #ts #ti, }
+            } else {
+                quote! { #ts #ti }
+            };
+            vs = if i < depth - 1 {
+                quote! { #vs #pi, }
+            } else {
+                quote! { #vs #pi }
+            };
+            ns = quote! { (#tr, #ns) };
+            refs = quote! { (&'a #tr, #refs) };
+            muts = quote! { (&'a mut #tr, #muts) };
+            nested = quote! { (#pr, #nested) };
+        }
+        out = quote! { #out
+            impl<#cstr> Unflattern for #ns {
+                fn unflattern(flat: (#ts)) -> Self {
+                    let (#vs) = flat;
+                    #nested
+                }
+                fn unflattern_ref<'a>(flat: &'a (#ts)) -> #refs where Self: 'a {
+                    let (#vs) = flat;
+                    #nested
+                }
+                fn unflattern_mut<'a>(flat: &'a mut (#ts)) -> #muts where Self: 'a {
+                    let (#vs) = flat;
+                    #nested
+                }
+            }
+        }
+    }
+    out
+}
+
 fn impl_all_contains(max_size: u8) -> TokenStream {
     let mut out = quote! {};
     for size in 1..max_size + 1 {
@@ -193,6 +288,255 @@ fn impl_all_contains(max_size: u8) -> TokenStream {
     out
 }
 
+/// ```ignore
+/// impl Lerp for f32 {
+///     fn lerp(&self, to: &Self, t: f32) -> Self {
+///         self + (to - self) * t
+///     }
+/// }
+/// impl<T0: Lerp, T1: Lerp> Lerp for (T0, T1) {
+///     fn lerp(&self, to: &Self, t: f32) -> Self {
+///         (self.0.lerp(&to.0, t), self.1.lerp(&to.1, t))
+///     }
+/// }
+/// ```
+fn impl_all_lerp(max_size: u8) -> TokenStream {
+    let mut out = quote! {
+        impl Lerp for f32 {
+            fn lerp(&self, to: &Self, t: f32) -> Self {
+                self + (to - self) * t
+            }
+        }
+        impl Lerp for f64 {
+            fn lerp(&self, to: &Self, t: f32) -> Self {
+                self + (to - self) * t as f64
+            }
+        }
+    };
+    for int in [
+        "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize",
+    ] {
+        let int = format_ident!("{int}");
+        out = quote! { #out
+            impl Lerp for #int {
+                fn lerp(&self, to: &Self, t: f32) -> Self {
+                    (*self as f64 + (*to as f64 - *self as f64) * t as f64).round() as #int
+                }
+            }
+        }
+    }
+    for size in 1..max_size + 1 {
+        let mut cstr = quote! {};
+        let mut ts = quote! {};
+        let mut vs = quote! {};
+        for i in 0..size {
+            let ti = format_ident!("T{i}");
+            let idx = syn::Index::from(i as usize);
+            cstr = quote! { #cstr #ti: Lerp, };
+            ts = quote! { #ts #ti, };
+            vs = quote! { #vs self.#idx.lerp(&to.#idx, t), };
+        }
+        out = quote! { #out
+            impl<#cstr> Lerp for (#ts) {
+                fn lerp(&self, to: &Self, t: f32) -> Self {
+                    (#vs)
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Tuples as `#[prop(construct)]` fields, elements are exposed as `_0`, `_1`, ...
+/// ```ignore
+/// impl<T0: 'static, T1: 'static> ConstructItem for (T0, T1) {
+///     type Params = (T0, T1);
+///     type Getters<'a> = TupleGetters<'a, (T0, T1)>;
+///     type Setters<'a> = TupleSetters<'a, (T0, T1)>;
+///     fn construct_item(params: Self::Params) -> Self {
+///         params
+///     }
+/// }
+/// impl<'a, T0, T1> TupleGetters<'a, (T0, T1)> {
+///     pub fn _0(self) -> Value<'a, T0> {
+///         Value::Ref(&self.0 .0)
+///     }
+///     // ...
+/// }
+/// ```
+fn impl_all_tuple_items(max_size: u8) -> TokenStream {
+    let mut out = quote! {};
+    for size in 1..max_size + 1 {
+        let mut ts = quote! {};
+        let mut statics = quote! {};
+        let mut diffs = quote! {};
+        let mut getters = quote! {};
+        let mut setters = quote! {};
+        let mut diff = quote! {};
+        let mut visits = quote! {};
+        let mut visits_mut = quote! {};
+        for i in 0..size {
+            let ti = format_ident!("T{i}");
+            let idx = syn::Index::from(i as usize);
+            let name = i.to_string();
+            let get = format_ident!("_{i}");
+            let set = format_ident!("set__{i}");
+            let stage = format_ident!("stage__{i}");
+            let commit = format_ident!("commit__{i}");
+            let mutate = format_ident!("with__{i}");
+            ts = quote! { #ts #ti, };
+            statics = quote! { #statics #ti: 'static, };
+            diffs = quote! { #diffs #ti: PartialEq + Clone + 'static, };
+            getters = quote! { #getters
+                pub fn #get(self) -> Value<'a, #ti> {
+                    Value::Ref(&self.0 .#idx)
+                }
+            };
+            setters = quote! { #setters
+                #[doc(hidden)]
+                pub fn #set(self, value: #ti) {
+                    self.0 .#idx = value;
+                }
+                #[doc(hidden)]
+                pub fn #stage(self, value: #ti) {
+                    self.0 .#idx = value;
+                }
+                #[doc(hidden)]
+                pub fn #commit(self) {}
+                #[doc(hidden)]
+                pub fn #mutate<R>(self, f: impl FnOnce(&mut #ti) -> R) -> R {
+                    f(&mut self.0 .#idx)
+                }
+            };
+            visits = quote! { #visits
+                visitor.visit(#name, &self.#idx);
+            };
+            visits_mut = quote! { #visits_mut
+                visitor.visit(#name, &mut self.#idx);
+            };
+            diff = quote! { #diff
+                if from.#idx != to.#idx {
+                    let value = to.#idx.clone();
+                    changes.push(Change::new(#name, move |host: &mut Self| {
+                        host.#idx = value.clone()
+                    }));
+                }
+            };
+        }
+        // the writers need the whole tuple type
+        for i in 0..size {
+            let ti = format_ident!("T{i}");
+            let idx = syn::Index::from(i as usize);
+            let write = format_ident!("write__{i}");
+            setters = quote! { #setters
+                #[doc(hidden)]
+                pub fn #write(self) -> PropWrite<(#ts), #ti> {
+                    PropWrite::new(
+                        |host: &mut (#ts), value: #ti| host.#idx = value,
+                        |host: &mut (#ts), value: #ti| host.#idx = value,
+                        |_| {},
+                        PropCommit::default(),
+                    )
+                }
+            };
+        }
+        out = quote! { #out
+            impl<#statics> Standalone for (#ts) {}
+            impl<#statics> ConstructItem for (#ts) {
+                type Params = (#ts);
+                type Getters<'a> = TupleGetters<'a, (#ts)>;
+                type Setters<'a> = TupleSetters<'a, (#ts)>;
+                fn construct_item(params: Self::Params) -> Self {
+                    params
+                }
+            }
+            impl<'a, #ts> TupleGetters<'a, (#ts)> {
+                #getters
+            }
+            impl<'a, #ts> TupleSetters<'a, (#ts)> {
+                #setters
+            }
+            impl<#diffs> Diff for (#ts) {
+                fn diff(from: &Self, to: &Self, changes: &mut Vec<Change<Self>>) {
+                    #diff
+                }
+            }
+            impl<#statics> VisitProps for (#ts) {
+                const HOST: &'static str = "tuple";
+                fn visit_props<V: PropVisitor>(&self, visitor: &mut V) {
+                    #visits
+                }
+                fn visit_props_mut<V: PropVisitorMut>(&mut self, visitor: &mut V) {
+                    #visits_mut
+                }
+            }
+            impl<#ts> Through for (#ts) {
+                type Target = Self;
+                fn through<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+                    f(self)
+                }
+                fn through_mut<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+                    f(self)
+                }
+            }
+        }
+    }
+    out
+}
+
+/// ```ignore
+/// impl<T0: ParamDefault, T1: ParamDefault> OptionalParams for (T0, T1) {
+///     type Optional = (Option<T0>, Option<T1>);
+///     fn unwrap_optional(optional: (Option<T0>, Option<T1>)) -> Option<(T0, T1)> {
+///         let (p0, p1) = optional;
+///         if p0.is_none() && p1.is_none() {
+///             None
+///         } else {
+///             Some((p0.or_else(T0::param_default)?, p1.or_else(T1::param_default)?))
+///         }
+///     }
+/// }
+/// impl<T0: OptionalParam, T1: OptionalParam> DefaultedParams for (T0, T1) {}
+/// ```
+fn impl_all_optional_params(max_size: u8) -> TokenStream {
+    let mut out = quote! {};
+    for size in 1..max_size + 1 {
+        let mut cstr = quote! {};
+        let mut ts = quote! {};
+        let mut dstr = quote! {};
+        let mut os = quote! {};
+        let mut ps = quote! {};
+        let mut nones = vec![];
+        let mut vs = quote! {};
+        for i in 0..size {
+            let ti = format_ident!("T{i}");
+            let pi = format_ident!("p{i}");
+            cstr = quote! { #cstr #ti: ParamDefault, };
+            dstr = quote! { #dstr #ti: OptionalParam, };
+            ts = quote! { #ts #ti, };
+            os = quote! { #os Option<#ti>, };
+            ps = quote! { #ps #pi, };
+            nones.push(quote! { #pi.is_none() });
+            vs = quote! { #vs #pi.or_else(#ti::param_default)?, };
+        }
+        out = quote! { #out
+            impl<#cstr> OptionalParams for (#ts) {
+                type Optional = (#os);
+                fn unwrap_optional(optional: (#os)) -> Option<(#ts)> {
+                    let (#ps) = optional;
+                    if #(#nones)&&* {
+                        None
+                    } else {
+                        Some((#vs))
+                    }
+                }
+            }
+            impl<#dstr> DefaultedParams for (#ts) {}
+        }
+    }
+    out
+}
+
 /// Generates single ExtractField trait implementation.
 /// `impl_extract_field(1, 3) will generate this:
 /// ```ignore
@@ -504,10 +848,12 @@ fn impl_contains(size: u8) -> TokenStream {
         }
         out = quote! { #out
             impl<I, #tin> Contains<I, #cnt> for #tfor { }
+            impl<S, B, #tin> Placeable<S, B, #cnt> for #tfor { }
         }
     }
     out = quote! { #out
         impl<#tin> Contains<Inclusive, #tfor> for #tfor { }
+        impl<S, B, #tin> Placeable<S, B, #tfor> for #tfor { }
     };
     out
 }
@@ -7,8 +7,8 @@ pub mod throw;
 
 pub mod prelude {
     pub use crate::context::Context;
-    pub use crate::derive::{DeriveConstruct, DeriveSegment};
+    pub use crate::derive::{DeriveConstruct, DeriveSegment, VirtualDesign};
     pub use crate::genlib;
-    pub use crate::proc::{Construct, Prop, Ref};
+    pub use crate::proc::{Construct, Prop, Ref, Update};
     pub use constructivism_macro_gen::implement_constructivism_macro;
 }
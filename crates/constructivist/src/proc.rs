@@ -1,7 +1,13 @@
 use crate::{context::Context, throw};
 use proc_macro2::{Ident, TokenStream};
-use quote::{format_ident, quote};
-use syn::{braced, parenthesized, parse::Parse, spanned::Spanned, token::Brace, Expr, Token, Type};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    braced, bracketed, parenthesized,
+    parse::Parse,
+    spanned::Spanned,
+    token::{Brace, Bracket},
+    Expr, LitFloat, LitInt, Token, Type,
+};
 
 pub trait ContextLike {
     fn path(&self, name: &'static str) -> TokenStream;
@@ -170,6 +176,8 @@ impl<V: Value> Params<V> {
 pub struct Construct<V: Value> {
     pub ty: Option<Type>,
     pub flattern: bool,
+    /// Already constructed base: `construct!(Button on (rect, node))`
+    pub on: Option<Expr>,
     pub params: Params<V>,
 }
 
@@ -185,6 +193,11 @@ impl<V: Value> Parse for Construct<V> {
             input.parse::<Token![*]>()?;
             flattern = false;
         }
+        let mut on = None;
+        if input.peek(syn::Ident) && input.fork().parse::<Ident>()? == "on" {
+            input.parse::<Ident>()?;
+            on = Some(Expr::parse_without_eager_brace(input)?);
+        }
         let params = if input.peek(Brace) {
             Params::braced(input)?
         } else {
@@ -193,6 +206,7 @@ impl<V: Value> Parse for Construct<V> {
         Ok(Construct {
             ty,
             flattern,
+            on,
             params,
         })
     }
@@ -225,10 +239,27 @@ impl<V: Value> Construct<V> {
             } else {
                 quote! {}
             };
+            if let Some(on) = &self.on {
+                let own = self.params.items.iter().map(|p| {
+                    let ident = &p.ident;
+                    quote! { #lib::assert_own_param(&params, &fields.#ident.field()); }
+                });
+                return Ok(quote! {{
+                    use #lib::traits::*;
+                    let fields = <<#ty as #lib::Construct>::Params as #lib::Singleton>::instance();
+                    let params = <<#ty as #lib::Construct>::MixedParams as #lib::Extractable>::as_params();
+                    #lib::assert_instantiable::<#ty>();
+                    #(#own)*
+                    #body
+                    let defined_params = params.defined();
+                    #lib::construct_on::<#ty, _, _>(defined_params, #on)#flattern
+                }});
+            }
             Ok(quote! {{
                 use #lib::traits::*;
                 let fields = <<#ty as #lib::Construct>::Params as #lib::Singleton>::instance();
                 let params = <<#ty as #lib::Construct>::ExpandedParams as #lib::Extractable>::as_params();
+                #lib::assert_instantiable::<#ty>();
                 #body
                 let defined_params = params.defined();
                 <#ty as #lib::Construct>::construct(defined_params)#flattern
@@ -243,62 +274,490 @@ impl<V: Value> Construct<V> {
     }
 }
 
+#[derive(Clone)]
+pub struct Update<V: Value> {
+    pub target: Expr,
+    pub construct: Construct<V>,
+    pub rebuild: bool,
+}
+
+impl<V: Value> Parse for Update<V> {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let target = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let construct: Construct<V> = input.parse()?;
+        if construct.ty.is_none() {
+            throw!(input, "Expected Construct type to update.");
+        }
+        let mut rebuild = false;
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            if !input.is_empty() {
+                let ident: Ident = input.parse()?;
+                if &ident.to_string() != "rebuild" {
+                    throw!(ident, "Expected `rebuild`");
+                }
+                rebuild = true;
+            }
+        }
+        Ok(Update {
+            target,
+            construct,
+            rebuild,
+        })
+    }
+}
+
+// update!(&mut button, Button { .hidden, .size: (5., 5.) }) becomes:
+// {
+//     let __target__: &mut _ = &mut button;
+//     let fields = <<Button as Construct>::Params as Singleton>::instance();
+//     let __value__ = true;
+//     fields.hidden.stage(<<Button as Construct>::NestedSequence as Unflattern>::unflattern_mut(&mut *__target__), __value__.into());
+//     ...
+//     fields.hidden.commit(<<Button as Construct>::NestedSequence as Unflattern>::unflattern_mut(&mut *__target__));
+//     ...
+// }
+impl<V: Value> Update<V> {
+    pub fn build(&self, ctx: Ref<V::Context>) -> syn::Result<TokenStream> {
+        let lib = ctx.path("constructivism");
+        let target = &self.target;
+        let Some(ty) = &self.construct.ty else {
+            throw!(target, "Expected Construct type to update.");
+        };
+        let seq = quote! {
+            <<#ty as #lib::Construct>::NestedSequence as #lib::Unflattern>::unflattern_mut(&mut *__target__)
+        };
+        let mut stage = quote! {};
+        let mut commit = quote! {};
+        let mut rebuild = quote! {};
+        for param in self.construct.params.items.iter() {
+            let ident = &param.ident;
+            let value = V::build(&param.value, ctx)?;
+            stage = quote! { #stage
+                let __value__ = #value;
+                fields.#ident.stage(#seq, __value__.into());
+            };
+            commit = quote! { #commit
+                fields.#ident.commit(#seq);
+            };
+            if self.rebuild {
+                rebuild = quote! { #rebuild
+                    fields.#ident.rebuild(#seq, &mut __rebuilt__);
+                };
+            }
+        }
+        if self.rebuild {
+            rebuild = quote! {
+                let mut __rebuilt__ = vec![];
+                #rebuild
+            };
+        }
+        Ok(quote! {{
+            let __target__: &mut _ = #target;
+            let fields = <<#ty as #lib::Construct>::Params as #lib::Singleton>::instance();
+            #stage
+            #commit
+            #rebuild
+        }})
+    }
+}
+
+pub enum PropStep {
+    // .position
+    Field(Ident),
+    // [3]
+    Index(Expr),
+    // ?
+    Option,
+}
+
+impl PropStep {
+    pub fn is_field(&self) -> bool {
+        matches!(self, PropStep::Field(_))
+    }
+}
+
 pub struct Prop {
     pub root: Type,
-    pub path: Vec<Ident>,
+    pub path: Vec<PropStep>,
 }
 
 impl Parse for Prop {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let root = input.parse()?;
         let mut path = vec![];
-        while input.peek(Token![.]) {
-            let dot = input.parse::<Token![.]>()?;
-            if input.is_empty() {
-                path.push(format_ident!("DOT_AUTOCOMPLETE_TOKEN", span = dot.span()))
+        loop {
+            if input.peek(Token![.]) {
+                let dot = input.parse::<Token![.]>()?;
+                if input.is_empty() {
+                    path.push(PropStep::Field(format_ident!(
+                        "DOT_AUTOCOMPLETE_TOKEN",
+                        span = dot.span()
+                    )))
+                } else if input.peek(LitInt) {
+                    // tuple elements: .0 becomes ._0()
+                    let idx = input.parse::<LitInt>()?;
+                    let ident = format_ident!("_{}", idx.base10_digits(), span = idx.span());
+                    path.push(PropStep::Field(ident));
+                } else if input.peek(LitFloat) {
+                    // .0.1 is lexed as the float literal
+                    let idx = input.parse::<LitFloat>()?;
+                    for part in idx.base10_digits().split('.') {
+                        let ident = format_ident!("_{}", part, span = idx.span());
+                        path.push(PropStep::Field(ident));
+                    }
+                } else {
+                    path.push(PropStep::Field(input.parse()?))
+                }
+            } else if input.peek(Bracket) {
+                let content;
+                bracketed!(content in input);
+                let index: Expr = content.parse()?;
+                check_index(&index)?;
+                path.push(PropStep::Index(index));
+            } else if input.peek(Token![?]) {
+                input.parse::<Token![?]>()?;
+                path.push(PropStep::Option);
             } else {
-                path.push(input.parse()?)
+                break;
             }
         }
         Ok(Prop { root, path })
     }
 }
 
+// The index is stored in the prop itself, so it has to be known at compile time
+fn check_index(index: &Expr) -> syn::Result<()> {
+    let constant = match index {
+        Expr::Lit(_) => true,
+        Expr::Path(path) => path.path.segments.last().is_some_and(|s| {
+            let name = s.ident.to_string();
+            name.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        }),
+        _ => false,
+    };
+    if !constant {
+        throw!(
+            index,
+            "prop! index must be a literal or a constant, runtime indices can't be stored in the prop."
+        );
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PropMode {
+    Get,
+    Set,
+    Stage,
+    Commit,
+}
+
+// Where the chain of the fallible prop is at the current step
+enum PropState {
+    // <Root as Construct>::Props<Lookup>, props take `host` as the first arg
+    Lookup(TokenStream),
+    // Getters/Setters of the item or the nested construct
+    Item(TokenStream),
+    // Plain reference after the container step
+    Raw(TokenStream),
+}
+
 impl Prop {
+    /// `Node.position.0`, `List.items[3].label`, `Node.parent?.position`
+    pub fn dotted_path(&self) -> String {
+        // `crate::Node` and `Node` are the same prop
+        let mut out = match &self.root {
+            Type::Path(path) => path.path.segments.last().to_token_stream().to_string(),
+            root => root.to_token_stream().to_string(),
+        }
+        .replace(' ', "");
+        for step in self.path.iter() {
+            match step {
+                PropStep::Field(field) => {
+                    let field = field.to_string();
+                    let tuple_idx = field
+                        .strip_prefix('_')
+                        .filter(|i| !i.is_empty() && i.chars().all(|c| c.is_ascii_digit()));
+                    out.push('.');
+                    out.push_str(tuple_idx.unwrap_or(&field));
+                }
+                PropStep::Index(index) => {
+                    out.push('[');
+                    out.push_str(&index.to_token_stream().to_string().replace(' ', ""));
+                    out.push(']');
+                }
+                PropStep::Option => out.push('?'),
+            }
+        }
+        out
+    }
+
     pub fn build(&self, ctx: &Context) -> syn::Result<TokenStream> {
         let lib = ctx.constructivism();
         let root = &self.root;
-        let mut get = quote! { <<#root as #lib::Construct>::Props<#lib::Lookup> as #lib::Singleton>::instance().getters() };
-        let mut set = quote! { <<#root as #lib::Construct>::Props<#lib::Lookup> as #lib::Singleton>::instance().setters() };
         if self.path.len() == 0 {
             throw!(self.root, "Missing property path.");
         }
-        let last = self.path.len() - 1;
-
-        for (idx, part) in self.path.iter().enumerate() {
-            let setter = format_ident!("set_{}", part);
-            if idx == 0 {
-                get = quote! { #get.#part(host) };
-            } else {
-                get = quote! { #get.#part() };
-            }
+        let PropStep::Field(field) = &self.path[0] else {
+            throw!(self.root, "Property path should start with the field.");
+        };
+        if self.path.len() > 1 {
+            return self.build_path(ctx);
+        }
+        let lookup = quote! { <<#root as #lib::Construct>::Props<#lib::Lookup> as #lib::Singleton>::instance() };
+        let writer = format_ident!("write_{}", field);
+        let path = self.dotted_path();
+        Ok(quote! {
+            #lib::build_prop(
+                #lib::PropId::new(#path),
+                |host| #lookup.getters().#field(host).into_value(),
+                |host, value| #lookup.setters().#writer().set(host, value),
+                |host, value| #lookup.setters().#writer().stage(host, value),
+                |host| #lookup.setters().#writer().commit(host),
+                || #lookup.setters().#writer().info(),
+            )
+        })
+    }
 
-            if idx == 0 && idx == last {
-                set = quote! { #set.#setter(host, value) };
-            } else if idx == last {
-                set = quote! { #set.#setter(value)};
-            } else if idx == 0 {
-                set = quote! { #set.#part(host) };
-            } else {
-                set = quote! { #set.#part() };
-            }
+    // Paths without containers still produce `Prop`, the steps can't fail:
+    // prop!(Node.position.0) becomes:
+    // build_prop(
+    //     id,
+    //     |host| {
+    //         let __v__ = <Lookup>.getters().position(host).into_value();
+    //         let __x__ = &*__v__;
+    //         Through::through(__x__, |__x__| Value::Val(getters(__x__)._0().into_value().get()))
+    //     },
+    //     |host, value| {
+    //         let __result__ = <Lookup>.setters().with_position(host, |__x__| {
+    //             Through::through_mut(__x__, |__x__| setters(__x__).write__0().set(__x__, value))
+    //         });
+    //         <Lookup>.setters().write_position().commit(host);
+    //         __result__
+    //     },
+    //     ...
+    // )
+    //
+    // prop!(List.items[3].label) becomes:
+    // TryProp::new(
+    //     "List.items[3].label",
+    //     |host| {
+    //         let __v__ = <Lookup>.getters().items(host).into_value();
+    //         let __x__ = &*__v__;
+    //         PropIndex::at(__x__, 3).and_then(|__x__| Through::through(__x__, |__x__| {
+    //             Some(Value::Val(getters(__x__).label().into_value().get()))
+    //         }))
+    //     },
+    //     |host, value| <Lookup>.setters().with_items(host, |__x__| {
+    //         PropIndex::at_mut(__x__, 3).and_then(|__x__| Through::through_mut(__x__, |__x__| {
+    //             let () = setters(__x__).write_label().set(__x__, value);
+    //             Some(())
+    //         }))
+    //     }).is_some(),
+    // )
+    fn build_path(&self, ctx: &Context) -> syn::Result<TokenStream> {
+        let lib = ctx.constructivism();
+        let root = &self.root;
+        let lookup = quote! { <<#root as #lib::Construct>::Props<#lib::Lookup> as #lib::Singleton>::instance() };
+        let get = self.build_step(
+            ctx,
+            PropMode::Get,
+            0,
+            PropState::Lookup(quote! { #lookup.getters() }),
+        )?;
+        let set = self.build_step(
+            ctx,
+            PropMode::Set,
+            0,
+            PropState::Lookup(quote! { #lookup.setters() }),
+        )?;
+        let stage = self.build_step(
+            ctx,
+            PropMode::Stage,
+            0,
+            PropState::Lookup(quote! { #lookup.setters() }),
+        )?;
+        let commit = self.build_step(
+            ctx,
+            PropMode::Commit,
+            0,
+            PropState::Lookup(quote! { #lookup.setters() }),
+        )?;
+        let path = self.dotted_path();
+        if !self.is_fallible() {
+            return Ok(quote! {
+                #lib::build_prop(
+                    #lib::PropId::new(#path),
+                    |host| #get,
+                    |host, value| #set,
+                    |host, value| #stage,
+                    |host| #commit,
+                    // the path may go through setters and commits the nested hooks
+                    || #lib::PropCommit { hook: None, restage: true },
+                )
+            });
         }
-        get = quote! { #get.into_value() };
         Ok(quote! {
-            #lib::Prop::new(
+            #lib::TryProp::new(
+                #path,
                 |host| #get,
-                |host, value| #set
+                |host, value| (#set).is_some()
+            ).with_commit(
+                |host, value| (#stage).is_some(),
+                |host| { let _ = #commit; }
             )
         })
     }
+
+    fn is_fallible(&self) -> bool {
+        self.path.iter().any(|s| !s.is_field())
+    }
+
+    fn build_step(
+        &self,
+        ctx: &Context,
+        mode: PropMode,
+        idx: usize,
+        state: PropState,
+    ) -> syn::Result<TokenStream> {
+        let lib = ctx.constructivism();
+        let get = mode == PropMode::Get;
+        // steps of the fallible path return `Option`
+        let some = |value: TokenStream| {
+            if self.is_fallible() {
+                quote! { Some(#value) }
+            } else {
+                value
+            }
+        };
+        let ok = if self.is_fallible() {
+            quote! { Some(()) }
+        } else {
+            quote! {}
+        };
+        let Some(step) = self.path.get(idx) else {
+            return Ok(match (mode, state) {
+                (PropMode::Get, PropState::Raw(x)) => {
+                    some(quote! { #lib::Value::Val(::std::clone::Clone::clone(#x)) })
+                }
+                (PropMode::Set | PropMode::Stage, PropState::Raw(x)) => {
+                    quote! {{ *#x = value; #ok }}
+                }
+                (PropMode::Commit, PropState::Raw(x)) => quote! {{ let _ = #x; #ok }},
+                (_, PropState::Lookup(_) | PropState::Item(_)) => {
+                    throw!(self.root, "Unexpected end of the property path.");
+                }
+            });
+        };
+        let last = idx == self.path.len() - 1;
+        Ok(match (step, state) {
+            (PropStep::Field(field), PropState::Lookup(e) | PropState::Item(e)) => {
+                let host = if idx == 0 {
+                    quote! { host }
+                } else {
+                    quote! {}
+                };
+                let (writer, with) = (
+                    format_ident!("write_{}", field),
+                    format_ident!("with_{}", field),
+                );
+                // the writers take the host, the item itself below the root
+                let this = if idx == 0 {
+                    quote! { host }
+                } else {
+                    quote! { __x__ }
+                };
+                let sep = if idx == 0 {
+                    quote! {,}
+                } else {
+                    quote! {}
+                };
+                if get && last {
+                    some(quote! { #lib::Value::Val(#e.#field(#host).into_value().get()) })
+                } else if get {
+                    let next =
+                        self.build_step(ctx, mode, idx + 1, PropState::Raw(quote! { __x__ }))?;
+                    quote! {{
+                        let __v__ = #e.#field(#host).into_value();
+                        let __x__ = &*__v__;
+                        #next
+                    }}
+                } else if last {
+                    // the writer of the read-only prop returns `ReadOnly`, it is passed up
+                    // to `build_prop` and makes the result `PropRef`
+                    let write = match mode {
+                        PropMode::Set => quote! { #e.#writer().set(#this, value) },
+                        PropMode::Stage => quote! { #e.#writer().stage(#this, value) },
+                        _ => quote! { #e.#writer().commit(#this) },
+                    };
+                    if self.is_fallible() {
+                        quote! {{ let () = #write; #ok }}
+                    } else {
+                        write
+                    }
+                } else {
+                    // `[get, set]` props are read, changed and written back, the hook of
+                    // the field is called after the nested value is set or committed
+                    let next =
+                        self.build_step(ctx, mode, idx + 1, PropState::Raw(quote! { __x__ }))?;
+                    let commit = match mode {
+                        PropMode::Stage => quote! {},
+                        _ => quote! { #e.#writer().commit(#this); },
+                    };
+                    quote! {{
+                        let __result__ = #e.#with(#host #sep |__x__| #next);
+                        #commit
+                        __result__
+                    }}
+                }
+            }
+            (PropStep::Field(_), PropState::Raw(x)) => {
+                let (through, items) = if get {
+                    (quote! { through }, quote! { getters })
+                } else {
+                    (quote! { through_mut }, quote! { setters })
+                };
+                let next = self.build_step(
+                    ctx,
+                    mode,
+                    idx,
+                    PropState::Item(quote! { #lib::#items(__x__) }),
+                )?;
+                quote! {
+                    #lib::Through::#through(#x, |__x__| #next)
+                }
+            }
+            (PropStep::Index(index), PropState::Raw(x)) => {
+                let at = if get {
+                    quote! { at }
+                } else {
+                    quote! { at_mut }
+                };
+                let next = self.build_step(ctx, mode, idx + 1, PropState::Raw(quote! { __x__ }))?;
+                quote! {
+                    #lib::PropIndex::#at(#x, #index).and_then(|__x__| #next)
+                }
+            }
+            (PropStep::Option, PropState::Raw(x)) => {
+                let as_ref = if get {
+                    quote! { as_ref }
+                } else {
+                    quote! { as_mut }
+                };
+                let next = self.build_step(ctx, mode, idx + 1, PropState::Raw(quote! { __x__ }))?;
+                quote! {
+                    ::std::option::Option::#as_ref(#x).and_then(|__x__| #next)
+                }
+            }
+            (PropStep::Index(index), _) => {
+                throw!(index, "Unexpected index, expected the field.");
+            }
+            (PropStep::Option, _) => {
+                throw!(self.root, "Unexpected `?`, expected the field.");
+            }
+        })
+    }
 }
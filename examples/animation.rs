@@ -0,0 +1,55 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    position: (f32, f32),
+    opacity: f32,
+    layer: u8,
+}
+
+// Props are `Copy`, so they can be stored in tweens and tracks and
+// applied to any host later.
+fn tween_position() {
+    let mut node = construct!(Node);
+    let tween = Tween::new(prop!(Node.position), (0., 0.), (100., 50.));
+
+    tween.sample(&mut node, 0.5);
+    assert_eq!(node.position, (50., 25.));
+
+    let eased = tween.clone().with_ease(ease::quad_in);
+    eased.sample(&mut node, 0.5);
+    assert_eq!(node.position, (25., 12.5));
+}
+
+fn timeline() {
+    let mut node = construct!(Node);
+    let timeline = Timeline::new()
+        .track(
+            Track::new(prop!(Node.opacity))
+                .key(0., 0.)
+                .key(1., 1.)
+                .key(2., 0.),
+        )
+        .track(
+            Track::new(prop!(Node.layer))
+                .key(0., 0)
+                .key_eased(4., 10, ease::cubic_in),
+        );
+    assert_eq!(timeline.duration(), 4.);
+
+    timeline.sample(&mut node, 0.5);
+    assert_eq!(node.opacity, 0.5);
+    assert_eq!(node.layer, 0);
+
+    timeline.sample(&mut node, 1.5);
+    assert_eq!(node.opacity, 0.5);
+
+    timeline.sample(&mut node, 10.);
+    assert_eq!(node.opacity, 0.);
+    assert_eq!(node.layer, 10);
+}
+
+fn main() {
+    tween_position();
+    timeline();
+}
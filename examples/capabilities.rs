@@ -0,0 +1,77 @@
+use constructivism::*;
+
+pub trait Focusable {
+    fn focus_ring(&self) -> f32;
+}
+
+pub trait Clickable {
+    fn click(&self) -> &'static str;
+}
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+#[construct(provides = Clickable)]
+pub struct Rect {
+    size: (f32, f32),
+}
+
+#[derive(Segment)]
+#[construct(provides = Focusable)]
+pub struct Input {
+    disabled: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+#[construct(provides = Clickable)]
+pub struct Button {
+    pressed: bool,
+}
+
+#[derive(Construct)]
+#[construct(Label -> Rect)]
+pub struct Label {
+    text: String,
+}
+
+impl Clickable for RectDesign {
+    fn click(&self) -> &'static str {
+        "rect"
+    }
+}
+
+impl Clickable for ButtonDesign {
+    fn click(&self) -> &'static str {
+        "button"
+    }
+}
+
+impl<T> Focusable for InputDesign<T> {
+    fn focus_ring(&self) -> f32 {
+        2.
+    }
+}
+
+fn queries() {
+    // provided by the Input segment
+    let focusable = design_as::<dyn Focusable, Button>();
+    assert_eq!(focusable.map(|f| f.focus_ring()), Some(2.));
+    assert!(design_as::<dyn Focusable, Label>().is_none());
+    assert!(design_as::<dyn Focusable, Input>().is_some());
+
+    // the first design in the sequence wins
+    let clickable = design_as::<dyn Clickable, Button>().unwrap();
+    assert_eq!(clickable.click(), "button");
+    let clickable = design_as::<dyn Clickable, Label>().unwrap();
+    assert_eq!(clickable.click(), "rect");
+    assert!(design_as::<dyn Clickable, Node>().is_none());
+}
+
+fn main() {
+    queries();
+}
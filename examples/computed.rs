@@ -0,0 +1,95 @@
+use constructivism::*;
+
+#[derive(Construct, Default)]
+#[prop(get = area, ty = f32)]
+pub struct Rect {
+    width: f32,
+    height: f32,
+}
+impl Rect {
+    pub fn area(&self) -> f32 {
+        self.width * self.height
+    }
+}
+
+#[derive(Construct)]
+#[construct(Button -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+#[derive(Construct)]
+pub struct Holder {
+    #[prop(construct)]
+    rect: Rect,
+}
+
+pub struct ProgressBar {
+    value: f32,
+    max: f32,
+}
+impl ProgressBar {
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value.min(self.max);
+    }
+    pub fn ratio(&self) -> f32 {
+        self.value / self.max
+    }
+}
+
+derive_construct! {
+    seq => ProgressBar -> Nothing;
+    construct => (max: f32 = 100.) -> {
+        Self { value: 0., max }
+    };
+    props => {
+        value: f32 = [value, set_value];
+        /// Part of the progress done, from 0 to 1
+        ratio: f32 = [ratio];
+    };
+}
+
+fn read_only() {
+    let rect = construct!(Rect { .width: 2., .height: 3. });
+    // PropRef<Rect, f32>
+    let area = prop!(Rect.area);
+    assert_eq!(area.get(&rect).get(), 6.);
+
+    // through the sequence
+    let button = construct!(Button { .width: 4., .height: 4. });
+    assert_eq!(prop!(Rect.area).get(&button.1).get(), 16.);
+
+    // through the path, PropRef<Holder, f32>
+    let holder = construct!(Holder {
+        .rect: construct!(Rect { .width: 3., .height: 3. })
+    });
+    let area: PropRef<Holder, f32> = prop!(Holder.rect.area);
+    assert_eq!(area.get(&holder).get(), 9.);
+}
+
+fn computed() {
+    let mut bar = construct!(ProgressBar { .max: 200. });
+    let ratio = prop!(ProgressBar.ratio);
+    prop!(ProgressBar.value).set(&mut bar, 50.);
+    assert_eq!(ratio.get(&bar).get(), 0.25);
+    assert_eq!(getters(&bar).ratio().get(), 0.25);
+
+    // Compile error, read-only props have no setters:
+    // setters(&mut bar).set_ratio(0.5);
+    //     no method named `set_ratio` found for struct `Setters<'a>` in the current scope
+}
+
+fn diff_ignores_read_only() {
+    let a = construct!(Rect { .width: 1., .height: 1. });
+    let b = construct!(Rect { .width: 2., .height: 1. });
+    assert_eq!(diff::<Rect>(&a, &b).paths(), vec!["width"]);
+}
+
+fn main() {
+    read_only();
+    computed();
+    diff_ignores_read_only();
+}
@@ -0,0 +1,95 @@
+use std::ops::Bound;
+
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Slider {
+    #[prop(range = 0.0..=1.0, step = 0.01)]
+    value: f32,
+    #[prop(range = 0..=100, on_invalid = reject, unit = "px")]
+    width: i32,
+    #[param(default = 4)]
+    #[prop(range = 1..8, on_invalid = reject)]
+    ticks: u8,
+}
+
+// Compile error, there is nothing to clamp to beyond the excluded end:
+// #[prop(range = 0.0..1.0)]
+//     Clamped #[prop(range = ..)] should include the end, use `..=` or `on_invalid = reject`
+
+pub struct Volume {
+    level: u8,
+}
+impl Volume {
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+    pub fn set_level(&mut self, level: u8) {
+        self.level = level;
+    }
+}
+
+derive_construct! {
+    seq => Volume -> Nothing;
+    construct => (level: u8 = 5) -> {
+        Self { level }
+    };
+    props => {
+        #[prop(range = 0..=11)]
+        level: u8 = [level, set_level];
+    };
+}
+
+fn setters() {
+    let mut slider = construct!(Slider);
+    // clamped
+    prop!(Slider.value).set(&mut slider, 2.);
+    assert_eq!(slider.value, 1.);
+
+    // rejected
+    prop!(Slider.width).set(&mut slider, 50);
+    prop!(Slider.width).set(&mut slider, 500);
+    assert_eq!(slider.width, 50);
+    prop!(Slider.ticks).set(&mut slider, 8);
+    assert_eq!(slider.ticks, 4);
+
+    let mut volume = construct!(Volume);
+    prop!(Volume.level).set(&mut volume, 20);
+    assert_eq!(volume.level, 11);
+}
+
+fn construct() {
+    let slider = construct!(Slider { .value: -1. });
+    assert_eq!(slider.value, 0.);
+    let volume = construct!(Volume { .level: 12 });
+    assert_eq!(volume.level, 11);
+
+    // rejected params fall back to the default
+    let slider = construct!(Slider { .width: 101, .ticks: 0 });
+    assert_eq!(slider.width, 0);
+    assert_eq!(slider.ticks, 4);
+}
+
+fn metadata() {
+    let value = describe!(Slider).meta_value();
+    assert_eq!(value.name, "value");
+    let range = value.range.unwrap();
+    assert_eq!(range.start, Bound::Included(0.));
+    assert_eq!(range.end, Bound::Included(1.));
+    assert_eq!(value.step, Some(0.01));
+    assert!(!value.reject);
+
+    let width = describe!(Slider).meta_width();
+    assert_eq!(width.unit, Some("px"));
+    assert!(width.reject);
+    assert!(width.range.unwrap().contains(&100));
+
+    let level = describe!(Volume).meta_level();
+    assert!(level.step.is_none());
+}
+
+fn main() {
+    setters();
+    construct();
+    metadata();
+}
@@ -0,0 +1,150 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use constructivism::*;
+
+#[derive(Construct, Clone, Default, PartialEq, Debug)]
+pub struct Label {
+    text: String,
+    size: f32,
+}
+
+#[derive(Construct, Clone)]
+pub struct Item {
+    #[prop(construct)]
+    label: Label,
+    checked: bool,
+}
+
+#[derive(Construct)]
+pub struct List {
+    items: Vec<Item>,
+    named: HashMap<&'static str, Item>,
+}
+
+#[derive(Construct, Clone, Default)]
+pub struct Node {
+    position: (f32, f32),
+    parent: Option<Box<Node>>,
+    shared: Option<Rc<RefCell<Node>>>,
+}
+
+// Plain `Box<T>` fields are expanded without `?` or `[index]`
+#[derive(Construct)]
+pub struct Tree {
+    root: Box<Node>,
+}
+
+// Containers behind the getter and the setter are written back through the setter
+pub struct Menu {
+    entries: Vec<Item>,
+    changes: u32,
+}
+impl Menu {
+    pub fn entries(&self) -> Vec<Item> {
+        self.entries.clone()
+    }
+    pub fn set_entries(&mut self, entries: Vec<Item>) {
+        self.entries = entries;
+        self.changes += 1;
+    }
+}
+
+derive_construct! {
+    seq => Menu -> Nothing;
+    construct => () -> {
+        Self { entries: vec![], changes: 0 }
+    };
+    props => {
+        entries: Vec<Item> = [entries, set_entries];
+    };
+}
+
+fn item(text: &str) -> Item {
+    construct!(Item {
+        .label: construct!(Label { .text: text.to_string() })
+    })
+}
+
+fn index() {
+    let mut list = construct!(List {
+        .items: vec![item("a"), item("b")],
+    });
+    // TryProp<List, String>
+    let text = prop!(List.items[1].label.text);
+    assert_eq!(text.get(&list).unwrap().get(), "b");
+    assert!(text.set(&mut list, "c".to_string()));
+    assert_eq!(list.items[1].label.text, "c");
+
+    // missing items are reported, not panicked
+    let missing = prop!(List.items[5].checked);
+    assert!(missing.get(&list).is_none());
+    assert!(!missing.set(&mut list, true));
+
+    // whole item
+    let first = prop!(List.items[0]);
+    first.set(&mut list, item("z"));
+    assert_eq!(list.items[0].label.text, "z");
+
+    list.named.insert("x", item("x"));
+    let size = prop!(List.named["x"].label.size);
+    size.set(&mut list, 12.);
+    assert_eq!(list.named["x"].label.size, 12.);
+}
+
+fn option_and_pointers() {
+    let mut node = construct!(Node);
+    let parent = prop!(Node.parent?.position);
+    assert!(parent.get(&node).is_none());
+    assert!(!parent.set(&mut node, (1., 1.)));
+
+    node.parent = Some(Box::new(construct!(Node)));
+    assert!(parent.set(&mut node, (1., 1.)));
+    assert_eq!(parent.get(&node).unwrap().get(), (1., 1.));
+
+    // nested
+    let grand = prop!(Node.parent?.parent?.position);
+    assert!(grand.get(&node).is_none());
+
+    let shared = Rc::new(RefCell::new(construct!(Node)));
+    node.shared = Some(shared.clone());
+    prop!(Node.shared?.position).set(&mut node, (5., 5.));
+    assert_eq!(shared.borrow().position, (5., 5.));
+}
+
+fn boxed() {
+    let mut tree = construct!(Tree);
+    // Prop<Tree, (f32, f32)>, no step can fail
+    let position = prop!(Tree.root.position);
+    position.set(&mut tree, (2., 3.));
+    assert_eq!(tree.root.position, (2., 3.));
+    assert_eq!(position.get(&tree).get(), (2., 3.));
+}
+
+fn getters_and_setters() {
+    let mut menu = construct!(Menu);
+    menu.set_entries(vec![item("a")]);
+    let text = prop!(Menu.entries[0].label.text);
+    assert!(text.set(&mut menu, "b".to_string()));
+    assert_eq!(menu.entries[0].label.text, "b");
+    assert_eq!(menu.changes, 2);
+
+    // Compile error, the index is stored in the prop:
+    // let i = 0;
+    // prop!(Menu.entries[i]);
+    //     prop! index must be a literal or a constant, runtime indices can't be stored in the prop.
+    const LAST: usize = 0;
+    assert_eq!(
+        prop!(Menu.entries[LAST].label.text)
+            .get(&menu)
+            .unwrap()
+            .get(),
+        "b"
+    );
+}
+
+fn main() {
+    index();
+    option_and_pointers();
+    boxed();
+    getters_and_setters();
+}
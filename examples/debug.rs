@@ -0,0 +1,75 @@
+use constructivism::*;
+
+#[derive(Construct)]
+#[construct(debug)]
+pub struct Node {
+    hidden: bool,
+    #[prop(construct)]
+    position: (f32, f32),
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+#[construct(debug)]
+pub struct Rect {
+    #[prop(construct)]
+    size: Size,
+}
+
+#[derive(Construct, Default, Clone, Copy)]
+#[construct(debug)]
+pub struct Size {
+    width: f32,
+    height: f32,
+}
+
+#[derive(Segment)]
+#[construct(debug)]
+pub struct Input {
+    #[prop(label, set_label)]
+    disabled: bool,
+}
+impl Input {
+    // the getter is called instead of reading the field
+    pub fn label(&self) -> bool {
+        self.disabled
+    }
+    pub fn set_label(&mut self, disabled: bool) {
+        self.disabled = disabled;
+    }
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+#[construct(debug)]
+pub struct Button {
+    pressed: bool,
+}
+
+fn items() {
+    let size = construct!(Size { .width: 2., .height: 1. });
+    assert_eq!(format!("{:?}", size), "Size { width: 2.0, height: 1.0 }");
+    let rect = construct!(Rect { .size: size });
+    assert_eq!(
+        format!("{:?}", rect.0),
+        "Rect { size: Size { width: 2.0, height: 1.0 } }"
+    );
+}
+
+fn sequence() {
+    let button = construct!(Button { .disabled: true, .hidden: true });
+    assert_eq!(
+        format!("{:?}", SequenceDebug::<Button>::new(&button)),
+        "Button { pressed: false } -> Input { disabled: true } -> \
+         Rect { size: Size { width: 0.0, height: 0.0 } } -> \
+         Node { hidden: true, position: (0.0, 0.0) }"
+    );
+    // pretty printing is passed to the items
+    let pretty = format!("{:#?}", SequenceDebug::<Button>::new(&button));
+    assert!(pretty.starts_with("Button {\n    pressed: false,\n} -> Input {"));
+}
+
+fn main() {
+    items();
+    sequence();
+}
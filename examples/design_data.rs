@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+}
+impl NodeDesign {
+    pub fn hide(&self, node: &mut Node) {
+        node.hidden = true;
+    }
+}
+
+#[derive(Construct)]
+#[construct(Button -> Node)]
+#[construct(design = ButtonStyle)]
+pub struct Button {
+    color: (f32, f32, f32),
+}
+
+/// The design of the `Button`, initialized once on the first `design!(Button)`.
+pub struct ButtonStyle {
+    pub default_color: (f32, f32, f32),
+    pub palette: HashMap<&'static str, (f32, f32, f32)>,
+}
+
+impl Default for ButtonStyle {
+    fn default() -> Self {
+        let mut palette = HashMap::new();
+        palette.insert("primary", (0., 0.5, 1.));
+        palette.insert("danger", (1., 0., 0.));
+        ButtonStyle {
+            default_color: (0.5, 0.5, 0.5),
+            palette,
+        }
+    }
+}
+
+impl ButtonStyle {
+    pub fn paint(&self, button: &mut Button, name: &str) {
+        button.color = self
+            .palette
+            .get(name)
+            .copied()
+            .unwrap_or(self.default_color);
+    }
+}
+
+#[derive(Construct)]
+#[construct(IconButton -> Button)]
+pub struct IconButton {
+    icon: String,
+}
+
+fn data() {
+    let style = design!(Button);
+    assert_eq!(style.default_color, (0.5, 0.5, 0.5));
+    // the same instance every time
+    assert!(std::ptr::eq(style, design!(Button)));
+
+    let (mut button, mut node) = construct!(Button);
+    design!(Button).paint(&mut button, "danger");
+    assert_eq!(button.color, (1., 0., 0.));
+    // the base design is still reachable through Deref
+    design!(Button).hide(&mut node);
+    assert!(node.hidden);
+}
+
+fn derived() {
+    let (_, mut button, _) = construct!(IconButton { .icon: "+".to_string() });
+    // IconButtonDesign derefs to ButtonStyle
+    design!(IconButton).paint(&mut button, "primary");
+    assert_eq!(button.color, (0., 0.5, 1.));
+    assert_eq!(design!(IconButton).palette.len(), 2);
+}
+
+fn main() {
+    data();
+    derived();
+}
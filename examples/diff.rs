@@ -0,0 +1,78 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+    position: (f32, f32),
+}
+
+#[derive(Construct, Default, Clone, PartialEq)]
+pub struct Color {
+    r: f32,
+    g: f32,
+    b: f32,
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    size: (f32, f32),
+    #[prop(construct)]
+    background: Color,
+}
+
+#[derive(Segment)]
+pub struct Input {
+    disabled: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+fn diff_and_patch() {
+    let editor = construct!(Button);
+    let mut edited = construct!(Button {
+        .disabled,
+        .size: (10., 10.),
+    });
+    prop!(Button.background.r).set(&mut edited.2, 1.);
+
+    let patch = diff::<Button>(&editor, &edited);
+    assert_eq!(patch.paths(), vec!["disabled", "size", "background.r"]);
+
+    let mut game = construct!(Button { .hidden });
+    patch.apply(&mut game);
+    let (button, input, rect, node) = game;
+    assert!(!button.pressed);
+    assert!(input.disabled);
+    assert_eq!(rect.size, (10., 10.));
+    assert_eq!(rect.background.r, 1.);
+    // untouched props are kept
+    assert!(node.hidden);
+}
+
+fn diff_single_level() {
+    let a = construct!(Color);
+    let b = construct!(Color { .g: 0.5 });
+    let patch = diff::<Color>(&a, &b);
+    assert_eq!(patch.len(), 1);
+
+    let mut c = construct!(Color { .r: 1. });
+    patch.apply(&mut c);
+    assert!(
+        c == Color {
+            r: 1.,
+            g: 0.5,
+            b: 0.
+        }
+    );
+    assert!(diff::<Color>(&c, &c).is_empty());
+}
+
+fn main() {
+    diff_and_patch();
+    diff_single_level();
+}
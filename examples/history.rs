@@ -0,0 +1,81 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+    position: (f32, f32),
+}
+
+pub struct Volume {
+    level: f32,
+    changes: u32,
+}
+impl Volume {
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+    pub fn set_level(&mut self, level: f32) {
+        self.changes += 1;
+        self.level = level.clamp(0., 1.);
+    }
+}
+
+derive_construct! {
+    seq => Volume -> Nothing;
+    construct => (level: f32 = 1.) -> {
+        Self { level, changes: 0 }
+    };
+    props => {
+        level: f32 = [level, set_level];
+    };
+}
+
+fn undo_redo() {
+    let mut node = construct!(Node);
+    let mut history = PropHistory::new();
+    history.set(&mut node, prop!(Node.position), (10., 10.));
+    history.set(&mut node, prop!(Node.hidden), true);
+
+    assert!(history.undo(&mut node));
+    assert!(!node.hidden);
+    assert_eq!(node.position, (10., 10.));
+
+    assert!(history.undo(&mut node));
+    assert_eq!(node.position, (0., 0.));
+    assert!(!history.undo(&mut node));
+
+    assert!(history.redo(&mut node));
+    assert_eq!(node.position, (10., 10.));
+}
+
+fn transactions() {
+    let mut node = construct!(Node);
+    let mut history = PropHistory::new();
+    history.transaction(&mut node, |history, node| {
+        history.set(node, prop!(Node.position), (5., 5.));
+        history.set(node, prop!(Node.hidden), true);
+    });
+    history.undo(&mut node);
+    assert_eq!(node.position, (0., 0.));
+    assert!(!node.hidden);
+    history.redo(&mut node);
+    assert_eq!(node.position, (5., 5.));
+    assert!(node.hidden);
+}
+
+// Undo of getter/setter props goes through the setter
+fn undo_through_setter() {
+    let mut volume = construct!(Volume);
+    let mut history = PropHistory::new();
+    history.set(&mut volume, prop!(Volume.level), 0.5);
+    assert_eq!(volume.changes, 1);
+    history.undo(&mut volume);
+    assert_eq!(volume.level, 1.);
+    assert_eq!(volume.changes, 2);
+}
+
+fn main() {
+    undo_redo();
+    transactions();
+    undo_through_setter();
+}
@@ -0,0 +1,166 @@
+use std::cell::RefCell;
+
+use constructivism::*;
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+fn log(msg: &str) {
+    LOG.with(|l| l.borrow_mut().push(msg.to_string()));
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with(|l| std::mem::take(&mut *l.borrow_mut()))
+}
+
+#[derive(Construct)]
+#[construct(after = Node::register)]
+pub struct Node {
+    id: usize,
+}
+impl Node {
+    fn register(&mut self) {
+        log("node");
+        self.id = 42;
+    }
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+#[construct(after = Rect::compute_area)]
+pub struct Rect {
+    size: (f32, f32),
+    #[param(skip)]
+    area: f32,
+}
+impl Rect {
+    fn compute_area(&mut self) {
+        log("rect");
+        self.area = self.size.0 * self.size.1;
+    }
+}
+
+#[derive(Segment)]
+#[construct(after = Input::after)]
+pub struct Input {
+    disabled: bool,
+}
+impl Input {
+    fn after(&mut self) {
+        log("input");
+    }
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+#[construct(after = button_after, finalize = button_finalize)]
+pub struct Button {
+    label: String,
+}
+
+fn button_after(_: &mut Button) {
+    log("button");
+}
+
+fn button_finalize((button, input, rect, node): &mut (Button, Input, Rect, Node)) {
+    log("finalize");
+    button.label = format!("{} #{}", button.label, node.id);
+    input.disabled = rect.area == 0.;
+}
+
+#[derive(Construct)]
+#[construct(Toggle -> Input -> Rect)]
+#[construct(hooks = top_down)]
+pub struct Toggle {
+    on: bool,
+}
+
+// The base finalize hooks run before the finalize hook of the top construct
+#[derive(Construct)]
+#[construct(Panel -> Node)]
+#[construct(finalize = panel_finalize)]
+pub struct Panel {
+    title: String,
+}
+
+fn panel_finalize((panel, _): &mut (Panel, Node)) {
+    log("panel finalize");
+    panel.title = panel.title.to_uppercase();
+}
+
+#[derive(Construct)]
+#[construct(Dialog -> Input -> Panel)]
+#[construct(finalize = dialog_finalize)]
+pub struct Dialog {
+    modal: bool,
+}
+
+fn dialog_finalize((dialog, _, panel, _): &mut (Dialog, Input, Panel, Node)) {
+    log("dialog finalize");
+    dialog.modal = panel.title == "ALERT";
+}
+
+// `hooks = top_down` reverses the finalize hooks as well
+#[derive(Construct)]
+#[construct(Alert -> Panel)]
+#[construct(hooks = top_down, finalize = alert_finalize)]
+pub struct Alert {
+    urgent: bool,
+}
+
+fn alert_finalize((alert, panel, _): &mut (Alert, Panel, Node)) {
+    log("alert finalize");
+    // the panel finalize hook didn't run yet
+    alert.urgent = panel.title == "alert";
+}
+
+fn items() {
+    let (rect, node) = construct!(Rect { .size: (2., 3.) });
+    assert_eq!(rect.area, 6.);
+    assert_eq!(node.id, 42);
+    assert_eq!(take_log(), vec!["node", "rect"]);
+}
+
+fn sequence() {
+    let (button, input, rect, _) = construct!(Button { .label: "Ok".to_string() });
+    // hooks of the base levels run once, the finalize hook runs last
+    assert_eq!(
+        take_log(),
+        vec!["node", "rect", "input", "button", "finalize"]
+    );
+    assert_eq!(button.label, "Ok #42");
+    assert_eq!(rect.area, 0.);
+    assert!(input.disabled);
+}
+
+fn base_finalize() {
+    let (panel, _) = construct!(Panel { .title: "info".to_string() });
+    assert_eq!(panel.title, "INFO");
+    assert_eq!(take_log(), vec!["node", "panel finalize"]);
+
+    let (dialog, _, panel, _) = construct!(Dialog { .title: "alert".to_string() });
+    assert_eq!(panel.title, "ALERT");
+    assert!(dialog.modal);
+    assert_eq!(
+        take_log(),
+        vec!["node", "input", "panel finalize", "dialog finalize"]
+    );
+}
+
+fn top_down() {
+    let _ = construct!(Toggle);
+    assert_eq!(take_log(), vec!["input", "rect", "node"]);
+
+    let (alert, panel, _) = construct!(Alert { .title: "alert".to_string() });
+    assert!(alert.urgent);
+    assert_eq!(panel.title, "ALERT");
+    assert_eq!(take_log(), vec!["node", "alert finalize", "panel finalize"]);
+}
+
+fn main() {
+    items();
+    sequence();
+    base_finalize();
+    top_down();
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use constructivism::*;
+
+#[derive(Construct, Clone, Copy, Default, PartialEq, Debug)]
+pub struct Vec2 {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Construct)]
+#[prop(get = area, ty = f32)]
+pub struct Node {
+    #[prop(construct)]
+    position: Vec2,
+    #[prop(construct)]
+    size: (f32, f32),
+    items: Vec<Vec2>,
+}
+impl Node {
+    pub fn area(&self) -> f32 {
+        self.size.0 * self.size.1
+    }
+}
+
+fn paths() {
+    assert_eq!(prop!(Node.position).path(), "Node.position");
+    assert_eq!(prop!(Node.position.x).path(), "Node.position.x");
+    assert_eq!(prop!(Node.size.0).path(), "Node.size.0");
+    assert_eq!(prop!(Node.items[1].y).path(), "Node.items[1].y");
+    assert_eq!(prop!(Node.area).path(), "Node.area");
+    assert_eq!(
+        format!("{:?}", prop!(Node.position.x)),
+        "Prop(Node.position.x)"
+    );
+    assert_eq!(format!("{}", prop!(Node.area).id()), "Node.area");
+}
+
+fn identity() {
+    // the same path is the same prop
+    assert_eq!(prop!(Node.position.x), prop!(Node.position.x));
+    assert_ne!(prop!(Node.position.x), prop!(Node.position.y));
+    // the hash is stable
+    assert_eq!(
+        prop!(Node.position).id().as_u64(),
+        PropId::new("Node.position").as_u64()
+    );
+
+    // composed props have own ids
+    let x = prop!(Node.position).then(prop!(Vec2.x));
+    let y = prop!(Node.position).then(prop!(Vec2.y));
+    assert_ne!(x.id(), prop!(Node.position.x).id());
+    assert_ne!(x.id(), y.id());
+    // there is no single path, the id has the paths of the parts
+    assert_eq!(x.id().path(), None);
+    assert_eq!(x.id().parts(), ["Node.position", "Vec2.x"]);
+    assert_eq!(format!("{}", x.id()), "Node.position -> Vec2.x");
+
+    // mapped props are not the source prop
+    let double = prop!(Node.position.x).map(|x| x * 2., |x| x / 2.);
+    assert_ne!(double.id(), prop!(Node.position.x).id());
+    assert_eq!(double.id().parts(), ["Node.position.x", "map"]);
+    // the id of the map is stable, but shared by all the maps of the prop
+    assert_eq!(
+        double.id().as_u64(),
+        PropId::new("Node.position.x")
+            .then(PropId::new("map"))
+            .as_u64()
+    );
+    let half = prop!(Node.position.x).map(|x| x / 2., |x| x * 2.);
+    assert_eq!(half.id(), double.id());
+    let half = half.with_id(PropId::new("Node.position.x/2"));
+    assert_ne!(half.id(), double.id());
+
+    // the path starts from the type name
+    assert_eq!(prop!(crate::Node.position), prop!(Node.position));
+
+    // props created by hand are identified by the given path
+    let a = Prop::new("Vec2.x", |v: &Vec2| Value::Ref(&v.x), |v, x| v.x = x);
+    let b = Prop::new("Vec2.y", |v: &Vec2| Value::Ref(&v.y), |v, y| v.y = y);
+    assert_ne!(a, b);
+    assert_eq!(a, prop!(Vec2.x));
+}
+
+fn keys() {
+    let mut tracks = HashMap::new();
+    tracks.insert(prop!(Node.position.x), vec![0., 1.]);
+    tracks.insert(prop!(Node.position.y), vec![2.]);
+    assert_eq!(tracks[&prop!(Node.position.x)], vec![0., 1.]);
+
+    let mut log = vec![];
+    let mut node = construct!(Node);
+    for (prop, keys) in tracks.iter() {
+        prop.set(&mut node, keys[0]);
+        log.push(prop.path());
+    }
+    log.sort();
+    assert_eq!(log, vec!["Node.position.x", "Node.position.y"]);
+    assert_eq!(node.position, Vec2 { x: 0., y: 2. });
+}
+
+fn main() {
+    paths();
+    identity();
+    keys();
+}
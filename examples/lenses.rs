@@ -0,0 +1,107 @@
+use constructivism::*;
+
+#[derive(Construct, Clone, Copy, Default, PartialEq, Debug)]
+pub struct Vec2 {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Construct)]
+pub struct Node {
+    position: Vec2,
+    // radians
+    rotation: f32,
+}
+
+#[derive(Construct)]
+pub struct Frame {
+    #[prop(commit = snap)]
+    origin: Vec2,
+}
+impl Frame {
+    fn snap(&mut self) {
+        self.origin.x = self.origin.x.round();
+        self.origin.y = self.origin.y.round();
+    }
+}
+
+pub struct Label {
+    offset: Vec2,
+}
+impl Label {
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+    pub fn set_offset(&mut self, offset: Vec2) {
+        self.offset = offset;
+    }
+}
+
+derive_construct! {
+    seq => Label -> Nothing;
+    construct => () -> {
+        Self { offset: Vec2::default() }
+    };
+    props => {
+        offset: Vec2 = [offset, set_offset];
+    };
+}
+
+fn compose() {
+    let mut node = construct!(Node);
+    // Prop<Node, f32>
+    let x = prop!(Node.position).then(prop!(Vec2.x));
+    x.set(&mut node, 5.);
+    assert_eq!(node.position, Vec2 { x: 5., y: 0. });
+    assert_eq!(*x.get(&node).as_ref(), 5.);
+
+    // getters and setters are called for the outer value
+    let mut label = construct!(Label);
+    let y = prop!(Label.offset).then(prop!(Vec2.y));
+    y.set(&mut label, 3.);
+    assert_eq!(label.offset, Vec2 { x: 0., y: 3. });
+    assert_eq!(y.get(&label).get(), 3.);
+}
+
+fn map() {
+    let mut node = construct!(Node);
+    let degrees = prop!(Node.rotation).map(|r| r.to_degrees(), |d| d.to_radians());
+    degrees.set(&mut node, 180.);
+    assert_eq!(node.rotation, std::f32::consts::PI);
+    assert_eq!(degrees.get(&node).get(), 180.);
+
+    // closures can capture as long as they are `Copy`
+    let scale = 100.;
+    let percent = prop!(Node.rotation).map(move |r| r * scale, move |p| p / scale);
+    percent.set(&mut node, 50.);
+    assert_eq!(node.rotation, 0.5);
+}
+
+fn commit() {
+    // the commit hook of the outer prop is called for the composed prop
+    let mut frame = construct!(Frame);
+    let x = prop!(Frame.origin).then(prop!(Vec2.x));
+    set_many(&mut frame, [(x, 2.4)]);
+    assert_eq!(frame.origin.x, 2.);
+}
+
+// Composed props are still `Copy` and work everywhere plain props do
+fn animate() {
+    let mut node = construct!(Node);
+    let x = prop!(Node.position).then(prop!(Vec2.x));
+    let copy = x;
+    let tween = Tween::new(x, 0., 10.);
+    tween.sample(&mut node, 0.5);
+    assert_eq!(copy.get(&node).get(), 5.);
+
+    let degrees = prop!(Node.rotation).map(|r| r.to_degrees(), |d| d.to_radians());
+    set_many(&mut node, [(degrees, 90.)]);
+    assert_eq!(node.rotation, std::f32::consts::FRAC_PI_2);
+}
+
+fn main() {
+    compose();
+    map();
+    commit();
+    animate();
+}
@@ -0,0 +1,54 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+}
+
+/// Exists only as the base of the widgets.
+#[derive(Construct)]
+#[construct(Widget -> Node)]
+#[construct(abstract)]
+pub struct Widget {
+    focused: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Widget)]
+pub struct Button {
+    pressed: bool,
+}
+
+/// Can't be used as the base.
+#[derive(Construct)]
+#[construct(Spacer -> Node)]
+#[construct(final)]
+pub struct Spacer {
+    size: f32,
+}
+
+// Compile errors:
+// construct!(Widget);
+//     `Widget` is abstract and can't be constructed directly
+//
+// #[derive(Construct)]
+// #[construct(Gap -> Spacer)]
+// pub struct Gap;
+//     `Spacer` is final and can't be extended
+
+fn modifiers() {
+    let (button, widget, node) = construct!(Button {
+        .focused: true,
+        .hidden: true,
+    });
+    assert!(!button.pressed);
+    assert!(widget.focused);
+    assert!(node.hidden);
+
+    let (spacer, _) = construct!(Spacer { .size: 4. });
+    assert_eq!(spacer.size, 4.);
+}
+
+fn main() {
+    modifiers();
+}
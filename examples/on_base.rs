@@ -0,0 +1,74 @@
+use std::cell::Cell;
+
+use constructivism::*;
+
+thread_local! {
+    static AFTER: Cell<u32> = const { Cell::new(0) };
+}
+
+#[derive(Construct)]
+#[construct(after = Node::after)]
+pub struct Node {
+    hidden: bool,
+}
+impl Node {
+    fn after(&mut self) {
+        AFTER.with(|a| a.set(a.get() + 1));
+    }
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    size: (f32, f32),
+}
+
+#[derive(Segment)]
+pub struct Input {
+    disabled: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+fn pooled() -> (Rect, Node) {
+    construct!(Rect { .size: (10., 5.), .hidden: true })
+}
+
+fn on_base() {
+    let (rect, node) = pooled();
+    assert_eq!(AFTER.with(|a| a.get()), 1);
+
+    // only Button and Input are constructed
+    let (button, input, rect, node) = construct!(Button on (rect, node) {
+        .pressed: true,
+        .disabled: true,
+    });
+    assert!(button.pressed);
+    assert!(input.disabled);
+    assert_eq!(rect.size, (10., 5.));
+    assert!(node.hidden);
+    // hooks of the base levels are not called again
+    assert_eq!(AFTER.with(|a| a.get()), 1);
+
+    // Compile error, the params of the base levels are rejected:
+    // construct!(Button on pooled() { .pressed: true, .size: (1., 1.) });
+    //     `size` is a param of the base and can't be passed to `construct!(.. on ..)`
+}
+
+fn nested() {
+    let base = pooled();
+    // nested output with `*`, as for the regular construct!
+    let (button, (input, (rect, _))) = construct!(Button * on base);
+    assert!(!button.pressed);
+    assert!(!input.disabled);
+    assert_eq!(rect.size, (10., 5.));
+}
+
+fn main() {
+    on_base();
+    nested();
+}
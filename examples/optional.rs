@@ -0,0 +1,130 @@
+use std::cell::Cell;
+
+use constructivism::*;
+
+thread_local! {
+    static TOOLTIPS: Cell<u32> = const { Cell::new(0) };
+}
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    size: (f32, f32),
+}
+
+#[derive(Segment)]
+#[construct(after = Tooltip::after)]
+pub struct Tooltip {
+    #[prop(tag = "text")]
+    text: String,
+    #[param(default = 0.5)]
+    delay: f32,
+}
+impl Tooltip {
+    fn after(&mut self) {
+        TOOLTIPS.with(|t| t.set(t.get() + 1));
+    }
+}
+
+impl<T> TooltipDesign<T> {
+    pub fn max_width(&self) -> f32 {
+        200.
+    }
+}
+
+// Compile error, the params of the optional segment can't be required:
+// #[param(required)]
+// text: String,
+//     `text` is a required param and can't be a param of the optional segment
+
+/// The same Button with or without tooltip.
+#[derive(Construct)]
+#[construct(Button -> ?Tooltip -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+fn without_tooltip() {
+    let (button, tooltip, rect, _) = construct!(Button {
+        .pressed: true,
+        .size: (10., 10.),
+    });
+    assert!(button.pressed);
+    assert!(tooltip.is_none());
+    assert_eq!(rect.size, (10., 10.));
+    assert_eq!(TOOLTIPS.with(|t| t.get()), 0);
+}
+
+fn with_tooltip() {
+    // passing any of the Tooltip params instantiates the segment
+    let (_, tooltip, ..) = construct!(Button {
+        .text: "Save",
+    });
+    let tooltip = tooltip.unwrap();
+    assert_eq!(tooltip.text, "Save");
+    assert_eq!(tooltip.delay, 0.5);
+    assert_eq!(TOOLTIPS.with(|t| t.get()), 1);
+
+    let (_, tooltip, ..) = construct!(Button { .delay: 1. });
+    let tooltip = tooltip.unwrap();
+    assert_eq!(tooltip.text, "");
+    assert_eq!(tooltip.delay, 1.);
+}
+
+fn on_base() {
+    let base = construct!(Rect { .size: (5., 5.) });
+    let (_, tooltip, rect, _) = construct!(Button on base { .text: "Open" });
+    assert_eq!(tooltip.unwrap().text, "Open");
+    assert_eq!(rect.size, (5., 5.));
+
+    // the rest of the sequence is updated as usual
+    let mut button = construct!(Button);
+    update!(&mut button, Button { .size: (20., 20.), .pressed: true });
+    assert!(button.0.pressed);
+    assert_eq!(button.2.size, (20., 20.));
+}
+
+fn sequence() {
+    // diff, reset and update! skip the missing segment
+    let mut plain = construct!(Button);
+    let mut tipped = construct!(Button { .text: "Save" });
+    let other = construct!(Button { .text: "Open", .pressed: true });
+    assert_eq!(diff::<Button>(&plain, &other).paths(), vec!["pressed"]);
+    let patch = diff::<Button>(&tipped, &other);
+    assert_eq!(patch.paths(), vec!["pressed", "text"]);
+    patch.apply(&mut tipped);
+    assert_eq!(tipped.1.as_ref().unwrap().text, "Open");
+
+    update!(&mut plain, Button { .text: "Close" });
+    assert!(plain.1.is_none());
+    update!(&mut tipped, Button { .delay: 2. });
+    assert_eq!(tipped.1.as_ref().unwrap().delay, 2.);
+
+    reset::<Button>(&mut plain);
+    reset::<Button>(&mut tipped);
+    assert!(!tipped.0.pressed);
+    assert_eq!(tipped.1.as_ref().unwrap().delay, 0.5);
+
+    // the props of the optional segment are still listed
+    let tagged = Button::props_tagged("text");
+    assert_eq!(tagged.len(), 1);
+    assert_eq!(tagged[0].name, "text");
+}
+
+fn design() {
+    // the design of the optional segment is still in the chain
+    assert_eq!(design!(Button).max_width(), 200.);
+}
+
+fn main() {
+    without_tooltip();
+    with_tooltip();
+    on_base();
+    sequence();
+    design();
+}
@@ -0,0 +1,85 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    size: (f32, f32),
+}
+
+impl RectDesign {
+    pub fn min_size(&self) -> (f32, f32) {
+        (8., 8.)
+    }
+}
+
+/// Only makes sense on top of a `Rect`.
+#[derive(Segment)]
+#[segment(requires = Rect)]
+pub struct Input {
+    disabled: bool,
+}
+
+impl<T> InputDesign<T> {
+    pub fn hit_area(&self) -> (f32, f32) {
+        let (w, h) = self.required().min_size();
+        (w + 4., h + 4.)
+    }
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+#[derive(Construct)]
+#[construct(Label -> Rect)]
+pub struct Label {
+    text: String,
+}
+
+// `Is<Rect>` through the Label
+#[derive(Construct)]
+#[construct(Link -> Input -> Label)]
+pub struct Link {
+    url: String,
+}
+
+// Compile error, `Node` is not `Is<Rect>`:
+// #[derive(Construct)]
+// #[construct(Icon -> Input -> Node)]
+// pub struct Icon;
+//     segment `Input` can't be inserted on `Node`
+//     note: `Node` has to extend the construct required by `Input`
+
+fn requires() {
+    let (button, input, rect, _) = construct!(Button {
+        .disabled: true,
+        .size: (10., 10.),
+    });
+    assert!(!button.pressed);
+    assert!(input.disabled);
+    assert_eq!(rect.size, (10., 10.));
+
+    let (link, _, label, ..) = construct!(Link {
+        .url: "https://example.com".to_string(),
+        .text: "example".to_string(),
+    });
+    assert_eq!(link.url, "https://example.com");
+    assert_eq!(label.text, "example");
+}
+
+fn design() {
+    assert_eq!(design!(Button).hit_area(), (12., 12.));
+    assert_eq!(design!(Link).hit_area(), (12., 12.));
+}
+
+fn main() {
+    requires();
+    design();
+}
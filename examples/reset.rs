@@ -0,0 +1,91 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+    position: (f32, f32),
+    // skipped params are left alone by default
+    #[param(skip = 1)]
+    generation: u32,
+    // unless asked to reset
+    #[param(skip, reset)]
+    clicks: u32,
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    #[param(default = (100., 100.))]
+    size: (f32, f32),
+    // required params are left alone as well
+    #[param(required)]
+    id: i32,
+    #[param(required, reset = 0)]
+    owner: i32,
+}
+
+#[derive(Segment)]
+pub struct Input {
+    disabled: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+pub struct Slider {
+    min: f32,
+    max: f32,
+    val: f32,
+}
+
+// custom constructors are called again with default params
+derive_construct! {
+    seq => Slider -> Nothing;
+    construct => (min: f32 = 0., max: f32 = 1., val: f32 = 0.5) -> {
+        Self { min, max, val: val.clamp(min, max) }
+    };
+}
+
+fn reset_sequence() {
+    let mut button = construct!(Button {
+        .pressed,
+        .disabled,
+        .size: (10., 10.),
+        .id: 7,
+        .owner: 3,
+        .hidden,
+    });
+    button.3.generation = 5;
+    button.3.clicks = 10;
+
+    // put back to the pool
+    reset::<Button>(&mut button);
+    let (button, input, rect, node) = button;
+    assert!(!button.pressed);
+    assert!(!input.disabled);
+    assert_eq!(rect.size, (100., 100.));
+    assert_eq!(rect.id, 7);
+    assert_eq!(rect.owner, 0);
+    assert!(!node.hidden);
+    assert_eq!(node.generation, 5);
+    assert_eq!(node.clicks, 0);
+}
+
+fn reset_item() {
+    let mut rect = construct!(Rect { .id: 1, .owner: 1, .size: (1., 1.) }).0;
+    rect.reset_defaults();
+    assert_eq!(rect.size, (100., 100.));
+
+    let mut slider = construct!(Slider { .min: 10., .max: 20. });
+    assert_eq!(slider.val, 10.);
+    slider.reset_defaults();
+    assert_eq!((slider.min, slider.max, slider.val), (0., 1., 0.5));
+}
+
+fn main() {
+    reset_sequence();
+    reset_item();
+}
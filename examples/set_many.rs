@@ -0,0 +1,191 @@
+use constructivism::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    size: (f32, f32),
+}
+
+// Values are written to the fields directly, `normalize` restores
+// the invariants after every write.
+pub struct Meter {
+    min: f32,
+    val: f32,
+    max: f32,
+}
+impl Meter {
+    fn normalize(&mut self) {
+        if self.max < self.min {
+            self.max = self.min;
+        }
+        self.val = self.val.max(self.min).min(self.max);
+    }
+}
+
+derive_construct! {
+    seq => Meter -> Nothing;
+    construct => (min: f32 = 0., max: f32 = 1., val: f32 = 0.) -> {
+        let mut pb = Self { min, val, max };
+        pb.normalize();
+        pb
+    };
+    props => {
+        #[prop(commit = normalize)]
+        min: f32 = value;
+        #[prop(commit = normalize)]
+        max: f32 = value;
+        #[prop(commit = normalize)]
+        val: f32 = value;
+    };
+}
+
+// The README progress bar, the setters keep the invariants
+pub struct ProgressBar {
+    min: f32,
+    val: f32,
+    max: f32,
+}
+
+impl ProgressBar {
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+    pub fn set_min(&mut self, min: f32) {
+        self.min = min;
+        if self.max < min {
+            self.max = min;
+        }
+        if self.val < min {
+            self.val = min;
+        }
+    }
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+    pub fn set_max(&mut self, max: f32) {
+        self.max = max;
+        if self.min > max {
+            self.min = max;
+        }
+        if self.val > max {
+            self.val = max;
+        }
+    }
+    pub fn val(&self) -> f32 {
+        self.val
+    }
+    pub fn set_val(&mut self, val: f32) {
+        self.val = val.max(self.min).min(self.max)
+    }
+}
+
+derive_construct! {
+    // Sequence
+    seq => ProgressBar -> Rect;
+
+    // Constructor, all params with default values
+    construct => (min: f32 = 0., max: f32 = 1., val: f32 = 0.) -> {
+        if max < min {
+            max = min;
+        }
+        val = val.min(max).max(min);
+        Self { min, val, max }
+    };
+
+    // Props using getters and setters
+    props => {
+        min: f32 = [min, set_min];
+        max: f32 = [max, set_max];
+        val: f32 = [val, set_val];
+    };
+}
+
+static NORMALIZED: AtomicUsize = AtomicUsize::new(0);
+
+// The same works for derived constructs
+#[derive(Construct)]
+pub struct Span {
+    #[prop(commit = normalize)]
+    start: f32,
+    #[prop(commit = normalize)]
+    end: f32,
+}
+impl Span {
+    fn normalize(&mut self) {
+        NORMALIZED.fetch_add(1, Ordering::Relaxed);
+        if self.end < self.start {
+            self.end = self.start;
+        }
+    }
+}
+
+fn set_one_by_one() {
+    let mut pb = construct!(Meter);
+    let min = prop!(Meter.min);
+    let max = prop!(Meter.max);
+    let val = prop!(Meter.val);
+
+    // every set is committed immediately, so `val` is clamped by the old `max`
+    val.set(&mut pb, 7.);
+    min.set(&mut pb, 5.);
+    max.set(&mut pb, 10.);
+    assert_eq!(pb.val, 5.);
+}
+
+fn set_atomically() {
+    let mut pb = construct!(Meter);
+    let min = prop!(Meter.min);
+    let max = prop!(Meter.max);
+    let val = prop!(Meter.val);
+
+    // all values are staged first, then committed
+    set_many(&mut pb, [(val, 7.), (min, 5.), (max, 10.)]);
+    assert_eq!(pb.min, 5.);
+    assert_eq!(pb.val, 7.);
+    assert_eq!(pb.max, 10.);
+
+    // the order doesn't matter
+    let mut other = construct!(Meter);
+    set_many(&mut other, [(max, 10.), (min, 5.), (val, 7.)]);
+    assert_eq!(other.val, 7.);
+}
+
+fn setters() {
+    let (mut pb, ..) = construct!(ProgressBar);
+    let min = prop!(ProgressBar.min);
+    let max = prop!(ProgressBar.max);
+    let val = prop!(ProgressBar.val);
+
+    // the values of the setters are staged again when all of them are in place
+    set_many(&mut pb, [(val, 7.), (min, 5.), (max, 10.)]);
+    assert_eq!((pb.min, pb.val, pb.max), (5., 7., 10.));
+
+    let (mut other, ..) = construct!(ProgressBar);
+    set_many(&mut other, [(max, 10.), (min, 5.), (val, 7.)]);
+    assert_eq!((other.min, other.val, other.max), (5., 7., 10.));
+}
+
+fn derived_commit() {
+    let mut span = construct!(Span);
+    prop!(Span.start).set(&mut span, 3.);
+    assert_eq!(span.end, 3.);
+
+    // the shared hook is called once
+    NORMALIZED.store(0, Ordering::Relaxed);
+    set_many(&mut span, [(prop!(Span.start), 1.), (prop!(Span.end), 2.)]);
+    assert_eq!((span.start, span.end), (1., 2.));
+    assert_eq!(NORMALIZED.load(Ordering::Relaxed), 1);
+}
+
+fn main() {
+    set_one_by_one();
+    set_atomically();
+    setters();
+    derived_commit();
+}
@@ -0,0 +1,61 @@
+use constructivism::*;
+
+#[derive(Construct)]
+#[prop(get = area, ty = f32)]
+pub struct Rect {
+    #[prop(construct)]
+    size: (f32, f32),
+    items: Vec<f32>,
+}
+impl Rect {
+    pub fn area(&self) -> f32 {
+        self.size.0 * self.size.1
+    }
+}
+
+#[derive(Construct)]
+#[construct(Button -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+static SIZE: Prop<Rect, (f32, f32)> = prop!(Rect.size);
+static WIDTH: Prop<Rect, f32> = prop!(Rect.size.0);
+const AREA: PropRef<Rect, f32> = prop!(Rect.area);
+const FIRST: TryProp<Rect, f32> = prop!(Rect.items[0]);
+const PRESSED: Prop<Button, bool> = prop!(Button.pressed);
+
+// global table of animatable properties
+static ANIMATABLE: &[Prop<Rect, f32>] = &[prop!(Rect.size.0), prop!(Rect.size.1)];
+
+fn statics() {
+    let mut rect = construct!(Rect);
+    SIZE.set(&mut rect, (2., 3.));
+    assert_eq!(AREA.get(&rect).get(), 6.);
+    WIDTH.set(&mut rect, 4.);
+    assert_eq!(rect.size, (4., 3.));
+    assert_eq!(SIZE.path(), "Rect.size");
+
+    assert!(!FIRST.set(&mut rect, 1.));
+    rect.items.push(0.);
+    assert!(FIRST.set(&mut rect, 1.));
+
+    let mut button = construct!(Button);
+    PRESSED.set(&mut button.0, true);
+    assert!(button.0.pressed);
+}
+
+fn table() {
+    let mut rect = construct!(Rect);
+    for (idx, prop) in ANIMATABLE.iter().enumerate() {
+        prop.set(&mut rect, idx as f32 + 1.);
+    }
+    assert_eq!(rect.size, (1., 2.));
+    let paths: Vec<_> = ANIMATABLE.iter().map(|p| p.path()).collect();
+    assert_eq!(paths, vec!["Rect.size.0", "Rect.size.1"]);
+}
+
+fn main() {
+    statics();
+    table();
+}
@@ -0,0 +1,68 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Rect {
+    #[prop(tag = "layout", group = "Layout")]
+    width: f32,
+    #[prop(tag = "layout", group = "Layout")]
+    height: f32,
+    #[prop(tag = "style", group = "Appearance")]
+    opacity: f32,
+}
+
+#[derive(Segment)]
+pub struct Input {
+    #[prop(tag = "style", tag = "state")]
+    disabled: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+pub struct Button {
+    #[prop(tag = "state")]
+    pressed: bool,
+    #[prop(tag = "style", group = "Appearance")]
+    color: (f32, f32, f32),
+}
+
+fn names(props: Vec<&'static PropInfo>) -> Vec<String> {
+    props
+        .iter()
+        .map(|p| format!("{}.{}", p.host, p.name))
+        .collect()
+}
+
+fn tagged() {
+    // the whole sequence, from the construct to the base
+    assert_eq!(
+        names(<Button as Construct>::props_tagged("style")),
+        vec!["Button.color", "Input.disabled", "Rect.opacity"]
+    );
+    assert_eq!(
+        names(Button::props_tagged("state")),
+        vec!["Button.pressed", "Input.disabled"]
+    );
+    assert_eq!(
+        names(Rect::props_tagged("layout")),
+        vec!["Rect.width", "Rect.height"]
+    );
+    assert!(Rect::props_tagged("state").is_empty());
+}
+
+fn describe() {
+    let opacity = describe!(Button).meta_opacity();
+    assert_eq!(opacity.tags, &["style"]);
+    assert_eq!(opacity.group, Some("Appearance"));
+
+    let disabled = describe!(Button).meta_disabled();
+    assert_eq!(disabled.tags, &["style", "state"]);
+    assert_eq!(disabled.group, None);
+
+    let width = Rect::PROPS[0];
+    assert_eq!(width.group, Some("Layout"));
+}
+
+fn main() {
+    tagged();
+    describe();
+}
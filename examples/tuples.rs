@@ -0,0 +1,85 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    position: (f32, f32),
+    // diffed by elements
+    #[prop(construct)]
+    bounds: ((f32, f32), (f32, f32)),
+    matrix: [f32; 4],
+}
+
+#[derive(Construct)]
+pub struct Span {
+    #[prop(commit = normalize)]
+    range: (f32, f32),
+}
+impl Span {
+    fn normalize(&mut self) {
+        self.range.1 = self.range.1.max(self.range.0);
+    }
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    size: (f32, f32),
+}
+
+fn tuples() {
+    let mut node = construct!(Node);
+    // Prop<Node, f32>
+    let x = prop!(Node.position.0);
+    x.set(&mut node, 10.);
+    assert_eq!(node.position, (10., 0.));
+    assert_eq!(x.get(&node).get(), 10.);
+
+    // elements are plain values
+    prop!(Node.bounds.1).set(&mut node, (5., 3.));
+    assert_eq!(node.bounds, ((0., 0.), (5., 3.)));
+
+    // through the sequence
+    let mut rect = construct!(Rect);
+    prop!(Rect.position.1).set(&mut rect.1, 2.);
+    assert_eq!(rect.1.position, (0., 2.));
+}
+
+fn commit_elements() {
+    let mut span = construct!(Span);
+    // the hook of the field is called when the element is set
+    prop!(Span.range.0).set(&mut span, 5.);
+    assert_eq!(span.range, (5., 5.));
+
+    // and when it is committed
+    let end = prop!(Span.range.1);
+    end.stage(&mut span, 1.);
+    assert_eq!(span.range, (5., 1.));
+    end.commit(&mut span);
+    assert_eq!(span.range, (5., 5.));
+}
+
+fn arrays() {
+    let mut node = construct!(Node);
+    // TryProp<Node, f32>
+    let m2 = prop!(Node.matrix[2]);
+    assert!(m2.set(&mut node, 1.));
+    assert_eq!(node.matrix, [0., 0., 1., 0.]);
+    assert!(prop!(Node.matrix[4]).get(&node).is_none());
+}
+
+fn diff_tuples() {
+    let a = construct!(Node);
+    let b = construct!(Node {
+        .position: (1., 0.),
+        .bounds: ((0., 0.), (1., 0.)),
+    });
+    let patch = diff::<Node>(&a, &b);
+    assert_eq!(patch.paths(), vec!["position", "bounds.1"]);
+}
+
+fn main() {
+    tuples();
+    commit_elements();
+    arrays();
+    diff_tuples();
+}
@@ -0,0 +1,80 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    hidden: bool,
+    position: (f32, f32),
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    size: (f32, f32),
+}
+
+#[derive(Segment)]
+pub struct Input {
+    disabled: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+pub struct Slider {
+    min: f32,
+    max: f32,
+    val: f32,
+}
+
+derive_construct! {
+    seq => Slider -> Rect;
+    construct => (min: f32 = 0., max: f32 = 1., val: f32 = 0.) -> {
+        let max = max.max(min);
+        Self { min, max, val: val.clamp(min, max) }
+    };
+    props => {
+        min: f32 = value;
+        max: f32 = value;
+        val: f32 = value;
+    };
+}
+
+// Params are routed to the props of the right level of the sequence
+fn update_button() {
+    let mut button = construct!(Button { .pressed });
+    update!(&mut button, Button {
+        .hidden,
+        .disabled,
+        .size: (5., 5.),
+    });
+    let (button, input, rect, node) = button;
+    assert!(button.pressed);
+    assert!(input.disabled);
+    assert_eq!(rect.size, (5., 5.));
+    assert!(node.hidden);
+    assert_eq!(node.position, (0., 0.));
+}
+
+// Custom constructors are not called by default
+fn update_slider() {
+    let mut slider = construct!(Slider { .max: 10. });
+    update!(&mut slider, Slider { .val: 20., .size: (100., 10.) });
+    assert_eq!(slider.0.val, 20.);
+    assert_eq!(slider.1.size, (100., 10.));
+}
+
+// `rebuild` re-runs them for the updated levels
+fn rebuild_slider() {
+    let mut slider = construct!(Slider { .max: 10. });
+    update!(&mut slider, Slider { .val: 20., .min: 5. }, rebuild);
+    assert_eq!((slider.0.min, slider.0.max, slider.0.val), (5., 10., 10.));
+}
+
+fn main() {
+    update_button();
+    update_slider();
+    rebuild_slider();
+}
@@ -0,0 +1,102 @@
+use constructivism::*;
+
+// the levels below the attached one are not searched
+#[derive(Construct)]
+#[construct(overrides = Expand)]
+pub struct Node {
+    hidden: bool,
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    size: (f32, f32),
+}
+
+#[derive(Segment)]
+#[construct(overrides = Expand)]
+pub struct Input {
+    disabled: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Rect)]
+#[construct(overrides = Expand)]
+pub struct Button {
+    pressed: bool,
+}
+
+#[derive(Construct)]
+#[construct(Checkbox -> Button)]
+pub struct Checkbox {
+    checked: bool,
+}
+
+#[derive(Construct)]
+#[construct(Field -> Input -> Rect)]
+pub struct Field {
+    value: String,
+}
+
+#[virtual_design(Rect)]
+pub trait Expand {
+    fn expand_to(&self, size: (f32, f32)) -> String;
+}
+
+impl Expand for RectDesign {
+    fn expand_to(&self, size: (f32, f32)) -> String {
+        format!("rect {}x{}", size.0, size.1)
+    }
+}
+
+impl Expand for NodeDesign {
+    fn expand_to(&self, _: (f32, f32)) -> String {
+        "node".to_string()
+    }
+}
+
+impl Expand for ButtonDesign {
+    fn expand_to(&self, size: (f32, f32)) -> String {
+        // the base implementation is still reachable
+        format!("button with {}", RectDesign.expand_to(size))
+    }
+}
+
+impl<T> Expand for InputDesign<T> {
+    fn expand_to(&self, _: (f32, f32)) -> String {
+        "input".to_string()
+    }
+}
+
+// generic code only knows the construct extends Rect
+fn expand<C: Construct + Is<Rect>>() -> String {
+    design!(C as Expand).expand_to((2., 1.))
+}
+
+fn dispatch() {
+    // Node is below Rect, its override is not used
+    assert_eq!(expand::<Rect>(), "rect 2x1");
+    assert_eq!(expand::<Button>(), "button with rect 2x1");
+    // Checkbox doesn't override, the Button's implementation is used
+    assert_eq!(expand::<Checkbox>(), "button with rect 2x1");
+    // segments are levels as well
+    assert_eq!(expand::<Field>(), "input");
+}
+
+// Compile error, `Node` doesn't extend `Rect` the trait is attached to:
+// design!(Node as Expand);
+//     the trait bound `(Node, ()): Contains<Inclusive, (Rect, (Node, ()))>` is not satisfied
+
+fn statics() {
+    // the static resolution still follows the sequence order
+    assert_eq!(
+        design!(Checkbox).expand_to((1., 1.)),
+        "button with rect 1x1"
+    );
+    assert_eq!(design!(Rect).expand_to((1., 1.)), "rect 1x1");
+}
+
+fn main() {
+    dispatch();
+    statics();
+}
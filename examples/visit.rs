@@ -0,0 +1,161 @@
+use std::any::Any;
+
+use constructivism::*;
+
+#[derive(Construct, Clone, Copy, Default, PartialEq, Debug)]
+pub struct Vec2 {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Construct)]
+#[prop(get = area, ty = f32)]
+pub struct Rect {
+    #[prop(construct)]
+    position: Vec2,
+    #[prop(construct)]
+    size: (f32, f32),
+    #[prop(range = 0.0..=1.0)]
+    opacity: f32,
+}
+impl Rect {
+    pub fn area(&self) -> f32 {
+        self.size.0 * self.size.1
+    }
+}
+
+#[derive(Segment)]
+pub struct Input {
+    disabled: bool,
+}
+
+#[derive(Construct)]
+#[construct(Button -> Input -> Rect)]
+pub struct Button {
+    pressed: bool,
+}
+
+/// Collects dotted paths and the `f32` values.
+#[derive(Default)]
+struct Collect {
+    path: Vec<String>,
+    floats: Vec<(String, f32)>,
+    names: Vec<String>,
+}
+
+impl Collect {
+    fn full(&self, name: &str) -> String {
+        let mut path = self.path.clone();
+        path.push(name.to_string());
+        path.join(".")
+    }
+}
+
+impl PropVisitor for Collect {
+    fn visit<T: 'static>(&mut self, name: &str, value: &T) {
+        let path = self.full(name);
+        if let Some(value) = (value as &dyn Any).downcast_ref::<f32>() {
+            self.floats.push((path.clone(), *value));
+        }
+        self.names.push(path);
+    }
+    fn enter(&mut self, name: &str) -> bool {
+        self.path.push(name.to_string());
+        true
+    }
+    fn exit(&mut self, _: &str) {
+        self.path.pop();
+    }
+    fn level(&mut self, host: &'static str) {
+        self.names.push(format!("<{host}>"));
+    }
+}
+
+/// Doubles every `f32` prop.
+struct Double;
+impl PropVisitorMut for Double {
+    fn visit<T: 'static>(&mut self, _: &str, value: &mut T) {
+        if let Some(value) = (value as &mut dyn Any).downcast_mut::<f32>() {
+            *value *= 2.;
+        }
+    }
+}
+
+fn visit() {
+    let rect = construct!(Rect { .position: Vec2 { x: 1., y: 2. }, .size: (3., 4.) });
+    let mut collect = Collect::default();
+    visit_props(&rect, &mut collect);
+    assert_eq!(
+        collect.names,
+        vec![
+            "position.x",
+            "position.y",
+            "size.0",
+            "size.1",
+            "opacity",
+            "area"
+        ]
+    );
+    assert_eq!(collect.floats[2], ("size.0".to_string(), 3.));
+    assert_eq!(collect.floats[5], ("area".to_string(), 12.));
+}
+
+fn visit_mut() {
+    let mut rect = construct!(Rect { .size: (1., 2.), .opacity: 0.75 });
+    visit_props_mut(&mut rect, &mut Double);
+    assert_eq!(rect.size, (2., 4.));
+    // written back through the setter, so the range is respected
+    assert_eq!(rect.opacity, 1.);
+}
+
+/// Visits `position` as the whole value.
+struct Shallow(Vec<String>);
+impl PropVisitor for Shallow {
+    fn visit<T: 'static>(&mut self, name: &str, _: &T) {
+        self.0.push(name.to_string());
+    }
+    fn enter(&mut self, name: &str) -> bool {
+        name != "position"
+    }
+    fn exit(&mut self, _: &str) {}
+}
+
+fn shallow() {
+    let rect = construct!(Rect);
+    let mut shallow = Shallow(vec![]);
+    rect.visit_props(&mut shallow);
+    assert_eq!(shallow.0, vec!["position", "0", "1", "opacity", "area"]);
+}
+
+fn sequence() {
+    let mut button = construct!(Button { .disabled: true, .opacity: 0.25 });
+    let mut collect = Collect::default();
+    visit_sequence::<Button, _>(&button, &mut collect);
+    assert_eq!(
+        collect.names,
+        vec![
+            "<Button>",
+            "pressed",
+            "<Input>",
+            "disabled",
+            "<Rect>",
+            "position.x",
+            "position.y",
+            "size.0",
+            "size.1",
+            "opacity",
+            "area"
+        ]
+    );
+
+    visit_sequence_mut::<Button, _>(&mut button, &mut Double);
+    let (_, _, rect) = &button;
+    assert_eq!(rect.opacity, 0.5);
+}
+
+fn main() {
+    visit();
+    visit_mut();
+    shallow();
+    sequence();
+}
@@ -0,0 +1,96 @@
+use constructivism::*;
+
+#[derive(Construct)]
+pub struct Node {
+    #[prop(construct)]
+    position: (f32, f32),
+}
+
+#[derive(Construct)]
+#[construct(Rect -> Node)]
+pub struct Rect {
+    #[prop(construct)]
+    size: (f32, f32),
+}
+
+#[derive(Segment)]
+pub struct Input {
+    disabled: bool,
+}
+
+pub struct Button {
+    label: String,
+    width: f32,
+    origin: (f32, f32),
+}
+
+derive_construct! {
+    seq => Button -> Input -> Rect;
+
+    // Rect, Node and Input are constructed first
+    construct => (label: String = "Button".to_string(), padding: f32 = 0.) with (rect: &Rect, node: &Node) -> {
+        Self {
+            label,
+            width: rect.size.0 - padding * 2.,
+            origin: node.position,
+        }
+    };
+
+    props => {
+        label: String = value;
+        width: f32 = value;
+    };
+}
+
+pub struct Badge {
+    text: String,
+}
+
+derive_construct! {
+    seq => Badge -> Button;
+
+    construct => (text: String = String::new()) with (button: &Button, input: &Input) -> {
+        let text = if input.disabled {
+            format!("{} ({text}, disabled)", button.label)
+        } else {
+            format!("{} ({text})", button.label)
+        };
+        Self { text }
+    };
+}
+
+// Compile error, `Button` needs the base levels to be constructed:
+// <Button as ConstructItem>::construct_item(("Button".to_string(), 0.));
+//     `Button` reads the base levels and can't be constructed alone
+//
+// Compile error, `with (..)` can only read the levels below:
+// construct => () with (badge: &Badge) -> { .. }
+//     `Badge` is not a level of the base sequence
+
+fn with_base() {
+    let (button, _, rect, _) = construct!(Button {
+        .size: (100., 20.),
+        .position: (5., 5.),
+        .padding: 10.,
+    });
+    assert_eq!(rect.size, (100., 20.));
+    assert_eq!(button.width, 80.);
+    assert_eq!(button.origin, (5., 5.));
+    assert_eq!(button.label, "Button");
+}
+
+fn nested() {
+    let (badge, button, ..) = construct!(Badge {
+        .text: "new".to_string(),
+        .label: "Save".to_string(),
+        .disabled: true,
+        .size: (10., 10.),
+    });
+    assert_eq!(badge.text, "Save (new, disabled)");
+    assert_eq!(button.width, 10.);
+}
+
+fn main() {
+    with_base();
+    nested();
+}
@@ -1,4 +1,10 @@
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::rc::Rc;
 
 pub mod traits {
     pub use super::AsField;
@@ -18,9 +24,32 @@ pub trait ConstructItem: Sized {
     type Params: Extractable;
     type Getters<'a>: Getters<'a, Self>;
     type Setters<'a>: Setters<'a, Self>;
-    fn construct_item(params: <Self::Params as Extractable>::Output) -> Self;
+    /// Props declared by this item, the base and segments are not included.
+    const PROPS: &'static [PropInfo] = &[];
+    /// Constructs the item alone, only `Standalone` items can be constructed this way.
+    fn construct_item(params: <Self::Params as Extractable>::Output) -> Self
+    where
+        Self: Standalone;
+    /// Fills `Option<&'static dyn Trait>` slot with the design of this item when it
+    /// provides the trait, see `design_as()` and `virtual_design()`.
+    fn provide_design(slot: &mut dyn Any) -> bool {
+        let _ = slot;
+        false
+    }
+    /// `#[construct(after = path::to::fn)]`, called by `Construct::construct` for
+    /// every level of the sequence.
+    fn after_construct(&mut self) {}
 }
 
+/// Items constructed without the base levels. Constructs reading the base with
+/// `construct => (..) with (rect: &Rect) -> { .. }` are built only together with the base.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` reads the base levels and can't be constructed alone",
+    label = "the constructor is declared with `with (..)`",
+    note = "use `construct!({Self})` to construct it with the base"
+)]
+pub trait Standalone {}
+
 /// Main building block of constructivism
 pub trait Construct: ConstructItem {
     type Base: Construct;
@@ -32,17 +61,57 @@ pub trait Construct: ConstructItem {
 
     type MixedParams: Extractable;
     type ExpandedParams: Extractable;
-    type NestedSequence: Flattern;
+    type NestedSequence: Flattern + DesignSequence;
 
+    /// Constructs the sequence and runs the `after` and `finalize` hooks.
     fn construct<P, const I: u8>(params: P) -> Self::NestedSequence where P: ExtractParams<
         I, Self::MixedParams,
         Value = <Self::MixedParams as Extractable>::Output,
         Rest = <<<Self::Base as Construct>::ExpandedParams as Extractable>::Input as AsParams>::Defined
     >;
+
+    /// Constructs the levels of this construct on top of the already constructed base
+    /// and runs their hooks, see `construct_on()`.
+    fn construct_on(
+        params: <Self::MixedParams as Extractable>::Output,
+        base: <Self::Base as Construct>::NestedSequence,
+    ) -> Self::NestedSequence;
+
+    /// Constructs the sequence without hooks, derived constructs build their base with it.
+    #[doc(hidden)]
+    fn construct_sequence<P, const I: u8>(params: P) -> Self::NestedSequence where P: ExtractParams<
+        I, Self::MixedParams,
+        Value = <Self::MixedParams as Extractable>::Output,
+        Rest = <<<Self::Base as Construct>::ExpandedParams as Extractable>::Input as AsParams>::Defined
+    >;
+
+    /// Runs the `finalize` hooks of the base constructs and this one, from the base
+    /// to the top or from the top to the base with `top_down`. Every hook gets the
+    /// flattened sequence of its own construct.
+    #[doc(hidden)]
+    fn finalize_sequence(seq: Self::NestedSequence, top_down: bool) -> Self::NestedSequence {
+        let _ = top_down;
+        seq
+    }
+
+    /// Props with the `#[prop(tag = ..)]` on every level of the sequence, segments included.
+    fn props_tagged(tag: &str) -> Vec<&'static PropInfo>
+    where
+        Self::NestedSequence: PropsSequence,
+    {
+        let mut props = vec![];
+        <Self::NestedSequence as PropsSequence>::collect_props(&mut props);
+        props.retain(|p| p.has_tag(tag));
+        props
+    }
 }
 
-pub trait Segment: ConstructItem {
+pub trait Segment: ConstructItem + Standalone + 'static {
+    /// The construct declared with `#[segment(requires = Rect)]`, `()` by default.
+    type Requires: Construct;
     type Params<T: Singleton + 'static>: Singleton;
+    /// Params of the optional segment `Button -> ?Tooltip -> Rect`, every value is `Option`.
+    type OptionalFields<T: Singleton + 'static>: Singleton;
     type Design<T: Singleton + 'static>: Singleton;
     type Props<M: 'static, T: Props<M> + 'static>: Singleton + Props<M>;
 }
@@ -63,10 +132,27 @@ impl<T: Segment> Construct for T {
         I, Self::MixedParams,
         Value = <Self::MixedParams as Extractable>::Output,
         Rest = <<<Self::Base as Construct>::ExpandedParams as Extractable>::Input as AsParams>::Defined
+    >{
+        let mut seq = Self::construct_sequence(params);
+        seq.0.after_construct();
+        seq
+    }
+    fn construct_sequence<P, const I: u8>(params: P) -> Self::NestedSequence where P: ExtractParams<
+        I, Self::MixedParams,
+        Value = <Self::MixedParams as Extractable>::Output,
+        Rest = <<<Self::Base as Construct>::ExpandedParams as Extractable>::Input as AsParams>::Defined
     >{
         let (params, _) = params.extract_params();
         (<T as ConstructItem>::construct_item(params), ())
     }
+    fn construct_on(
+        params: <Self::MixedParams as Extractable>::Output,
+        _: <Self::Base as Construct>::NestedSequence,
+    ) -> Self::NestedSequence {
+        let mut seq = (<T as ConstructItem>::construct_item(params), ());
+        seq.0.after_construct();
+        seq
+    }
 }
 
 pub struct Constructor<C: Construct + 'static>(PhantomData<C>);
@@ -86,7 +172,8 @@ impl<C: Construct + 'static> Constructor<C> {
         F: FnOnce(
             &<C as Construct>::Params,
             <<<C as Construct>::ExpandedParams as Extractable>::Input as AsParams>::Undefined
-        ) -> P
+        ) -> P,
+        C: Instantiable,
     {
         let fields = <<C as Construct>::Params as Singleton>::instance();
         let params = <<C as Construct>::ExpandedParams as Extractable>::as_params();
@@ -95,13 +182,160 @@ impl<C: Construct + 'static> Constructor<C> {
     }
 }
 
+/// Implemented for every construct without `#[construct(abstract)]`, required by `construct!`.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is abstract and can't be constructed directly",
+    label = "declared with #[construct(abstract)]",
+    note = "construct one of the types extending `{Self}` instead"
+)]
+pub trait Instantiable {}
+impl Instantiable for () {}
+impl<T: Segment> Instantiable for T {}
+
+/// Implemented for every construct without `#[construct(final)]`, required from the base.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is final and can't be extended",
+    label = "declared with #[construct(final)]"
+)]
+pub trait Extendable {}
+impl Extendable for () {}
+impl<T: Segment> Extendable for T {}
+
+#[doc(hidden)]
+pub fn assert_instantiable<C: Instantiable>() {}
+
+#[doc(hidden)]
+pub fn assert_extendable<C: Extendable>() {}
+
+/// Implemented by the params of the levels built by `construct!(C on base { .. })`
+/// for every param `P` they contain.
+#[diagnostic::on_unimplemented(
+    message = "`{P}` is a param of the base and can't be passed to `construct!(.. on ..)`",
+    label = "the base levels are already constructed",
+    note = "only the params of the levels above the base are accepted with `on`"
+)]
+pub trait OwnParam<P, I> {}
+
+#[doc(hidden)]
+pub fn assert_own_param<O: OwnParam<P, I>, P, I>(_: &O, _: &Field<P>) {}
+
+/// Implemented by the nested sequence of the base `B` when it contains the sequence `R`
+/// of the construct required by the segment `S` with `#[segment(requires = Rect)]`.
+#[diagnostic::on_unimplemented(
+    message = "segment `{S}` can't be inserted on `{B}`",
+    label = "the base doesn't satisfy #[segment(requires = ..)]",
+    note = "`{B}` has to extend the construct required by `{S}`"
+)]
+pub trait Placeable<S, B, R> {}
+impl<S, B> Placeable<S, B, ()> for () {}
+
+#[doc(hidden)]
+pub fn assert_placeable<S: Segment, B: Construct>()
+where
+    B::NestedSequence: Placeable<S, B, <S::Requires as Construct>::NestedSequence>,
+{
+}
+
+/// Walks the levels of the nested sequence for `ConstructItem::provide_design`,
+/// down to the level `until` inclusive when it is passed.
+pub trait DesignSequence {
+    fn find_design(slot: &mut dyn Any, until: Option<TypeId>) -> bool;
+}
+impl DesignSequence for () {
+    fn find_design(_: &mut dyn Any, _: Option<TypeId>) -> bool {
+        false
+    }
+}
+impl<H: ConstructItem + 'static, R: DesignSequence> DesignSequence for (H, R) {
+    fn find_design(slot: &mut dyn Any, until: Option<TypeId>) -> bool {
+        H::provide_design(slot) || (until != Some(TypeId::of::<H>()) && R::find_design(slot, until))
+    }
+}
+
+/// Runs `ConstructItem::after_construct` for every level of the nested sequence,
+/// the order is set with `#[construct(hooks = top_down)]`.
+pub trait AfterSequence {
+    fn after_top_down(&mut self);
+    fn after_bottom_up(&mut self);
+}
+impl AfterSequence for () {
+    fn after_top_down(&mut self) {}
+    fn after_bottom_up(&mut self) {}
+}
+impl<H: ConstructItem, R: AfterSequence> AfterSequence for (H, R) {
+    fn after_top_down(&mut self) {
+        self.0.after_construct();
+        self.1.after_top_down();
+    }
+    fn after_bottom_up(&mut self) {
+        self.1.after_bottom_up();
+        self.0.after_construct();
+    }
+}
+
+/// Implemented for `dyn Trait` by `#[virtual_design(Base)]`.
+pub trait VirtualDesign: 'static {
+    /// The level the trait is attached to.
+    type Base: Construct + 'static;
+    /// The design of the level the trait is attached to.
+    fn base() -> &'static Self;
+}
+
+/// The most derived implementation of the `#[virtual_design]` trait in the sequence
+/// of `C`: `design!(C as Expand)`. `C` has to extend the level the trait is attached to,
+/// the levels below it are not searched.
+pub fn virtual_design<C: Construct + Is<T::Base>, T: VirtualDesign + ?Sized>() -> &'static T {
+    let mut slot: Option<&'static T> = None;
+    C::NestedSequence::find_design(&mut slot, Some(TypeId::of::<T::Base>()));
+    slot.unwrap_or_else(T::base)
+}
+
+/// The first design in the sequence of `C`, segments included, declared with
+/// `#[construct(provides = Trait)]`: `design_as::<dyn Focusable, Button>()`
+pub fn design_as<T: ?Sized + 'static, C: Construct>() -> Option<&'static T> {
+    let mut slot: Option<&'static T> = None;
+    C::NestedSequence::find_design(&mut slot, None);
+    slot
+}
+
+/// Constructs `C` on top of the flattened base sequence: `construct!(Button on (rect, node))`.
+/// Only the params of `C` and its segments are accepted.
+pub fn construct_on<C: Construct + Instantiable, P, const I: u8>(
+    params: P,
+    base: <<C::Base as Construct>::NestedSequence as Flattern>::Output,
+) -> C::NestedSequence
+where
+    P: ExtractParams<
+        I,
+        C::MixedParams,
+        Value = <C::MixedParams as Extractable>::Output,
+        Rest = Params<()>,
+    >,
+    <C::Base as Construct>::NestedSequence: Unflattern,
+{
+    let (params, _) = params.extract_params();
+    C::construct_on(params, Unflattern::unflattern(base))
+}
+
 #[macro_export]
 macro_rules! design {
+    ($t:ty as $tr:path) => {
+        $crate::virtual_design::<$t, dyn $tr>()
+    };
     ($t:ty) => {
         <<$t as $crate::Construct>::Design as $crate::Singleton>::instance()
     };
 }
 
+/// Props metadata of the construct: `describe!(Slider).meta_value()`
+#[macro_export]
+macro_rules! describe {
+    ($t:ty) => {
+        <<$t as $crate::Construct>::Props<$crate::Lookup> as $crate::Singleton>::instance()
+            .descriptors()
+    };
+}
+
 pub struct NothingProps<M>(PhantomData<M>);
 impl<M> Singleton for NothingProps<M> {
     fn instance() -> &'static Self {
@@ -138,6 +372,7 @@ impl<'a> Setters<'a, ()> for NothingSetters<'a> {
     }
 }
 
+impl Standalone for () {}
 impl ConstructItem for () {
     type Params = ();
     type Getters<'a> = NothingGetters<'a>;
@@ -164,6 +399,130 @@ impl Construct for () {
     >{
         ()
     }
+    fn construct_sequence<P, const I: u8>(_: P) -> Self::NestedSequence where P: ExtractParams<
+        I, Self::MixedParams,
+        Value = <Self::MixedParams as Extractable>::Output,
+        Rest = <<<Self::Base as Construct>::ExpandedParams as Extractable>::Input as AsParams>::Defined
+    >{
+    }
+    fn construct_on(
+        _: <Self::MixedParams as Extractable>::Output,
+        _: <Self::Base as Construct>::NestedSequence,
+    ) -> Self::NestedSequence {
+    }
+}
+
+// Optional segments: `Button -> ?Tooltip -> Rect` holds `Option<Tooltip>` in the sequence.
+// The segment is constructed when at least one of its params is passed to `construct!`.
+/// Params of the optional segment: `(Option<T0>, Option<T1>)` for `(T0, T1)`,
+/// see `implement_constructivism_core!`.
+pub trait OptionalParams: Extractable {
+    type Optional: Extractable;
+    /// `None` if no params are passed, missing params are defaulted otherwise.
+    fn unwrap_optional(optional: <Self::Optional as Extractable>::Output) -> Option<Self::Output>;
+}
+
+impl OptionalParams for () {
+    type Optional = ();
+    fn unwrap_optional(_: Self::Optional) -> Option<Self::Output> {
+        None
+    }
+}
+
+/// The default value of the param, `None` for `#[param(required)]`.
+pub trait ParamDefault: Sized {
+    fn param_default() -> Option<Self>;
+}
+
+/// Implemented by the params with the default value, the optional segment can't
+/// have required params.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is a required param and can't be a param of the optional segment",
+    label = "the segment is optional, but `{Self}` is declared with #[param(required)]",
+    note = "give the param a default value or don't mark the segment with `?`"
+)]
+pub trait OptionalParam {}
+
+/// Params of the segment that can be optional, see `assert_optional()`.
+pub trait DefaultedParams {}
+impl DefaultedParams for () {}
+
+#[doc(hidden)]
+pub fn assert_optional<S: Segment>()
+where
+    <S as ConstructItem>::Params: DefaultedParams,
+{
+}
+
+impl<N: New<T>, T> New<T> for Option<N> {
+    fn new(from: T) -> Self {
+        Some(N::new(from))
+    }
+}
+
+impl<T: Segment + 'static> Standalone for Option<T> where
+    <T as ConstructItem>::Params: OptionalParams
+{
+}
+impl<T: Segment + 'static> ConstructItem for Option<T>
+where
+    <T as ConstructItem>::Params: OptionalParams,
+{
+    type Params = <<T as ConstructItem>::Params as OptionalParams>::Optional;
+    type Getters<'a> = TupleGetters<'a, Self>;
+    type Setters<'a> = TupleSetters<'a, Self>;
+    const PROPS: &'static [PropInfo] = T::PROPS;
+    fn construct_item(params: <Self::Params as Extractable>::Output) -> Self {
+        <<T as ConstructItem>::Params as OptionalParams>::unwrap_optional(params)
+            .map(T::construct_item)
+    }
+    fn provide_design(slot: &mut dyn Any) -> bool {
+        T::provide_design(slot)
+    }
+    fn after_construct(&mut self) {
+        if let Some(item) = self {
+            item.after_construct();
+        }
+    }
+}
+
+// Tuple and array fields are expanded by the prop path: `prop!(Node.position.0)`.
+// Tuple elements are exposed as `_0`, `_1`, ..., see `implement_constructivism_core!`.
+pub struct TupleGetters<'a, T>(&'a T);
+pub struct TupleSetters<'a, T>(&'a mut T);
+impl<'a, T: ConstructItem> Getters<'a, T> for TupleGetters<'a, T> {
+    fn from_ref(from: &'a T) -> Self {
+        TupleGetters(from)
+    }
+    fn into_value(self) -> Value<'a, T> {
+        Value::Ref(self.0)
+    }
+}
+impl<'a, T: ConstructItem> Setters<'a, T> for TupleSetters<'a, T> {
+    fn from_mut(from: &'a mut T) -> Self {
+        TupleSetters(from)
+    }
+}
+
+// Array elements are reached with index paths: `prop!(Node.matrix[2])`, see `PropIndex`.
+impl<T: 'static, const N: usize> Standalone for [T; N] {}
+impl<T: 'static, const N: usize> ConstructItem for [T; N] {
+    type Params = ([T; N],);
+    type Getters<'a> = TupleGetters<'a, Self>;
+    type Setters<'a> = TupleSetters<'a, Self>;
+    fn construct_item(params: Self::Params) -> Self {
+        params.0
+    }
+}
+
+impl<T: 'static, const N: usize> Through for [T; N] {
+    type Target = Self;
+    fn through<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+        f(self)
+    }
+    fn through_mut<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        f(self)
+    }
 }
 
 pub struct Params<T>(T);
@@ -349,6 +708,91 @@ impl Flattern for () {
     }
 }
 
+/// References to the items of the nested sequence: `(&A, (&B, ()))` for `(A, (B, ()))`.
+pub trait Nested {
+    type Ref<'a>
+    where
+        Self: 'a;
+    type Mut<'a>
+    where
+        Self: 'a;
+}
+impl Nested for () {
+    type Ref<'a> = ();
+    type Mut<'a> = ();
+}
+impl<H, R: Nested> Nested for (H, R) {
+    type Ref<'a>
+        = (&'a H, R::Ref<'a>)
+    where
+        Self: 'a;
+    type Mut<'a>
+        = (&'a mut H, R::Mut<'a>)
+    where
+        Self: 'a;
+}
+
+/// Borrows the flattened sequence as nested one, so it can be walked recursively.
+pub trait Unflattern: Flattern + Nested {
+    fn unflattern(flat: Self::Output) -> Self;
+    fn unflattern_ref<'a>(flat: &'a Self::Output) -> Self::Ref<'a>
+    where
+        Self: 'a;
+    fn unflattern_mut<'a>(flat: &'a mut Self::Output) -> Self::Mut<'a>
+    where
+        Self: 'a;
+}
+impl Unflattern for () {
+    fn unflattern(_: Self::Output) -> Self {}
+    fn unflattern_ref<'a>(_: &'a Self::Output) -> Self::Ref<'a>
+    where
+        Self: 'a,
+    {
+    }
+    fn unflattern_mut<'a>(_: &'a mut Self::Output) -> Self::Mut<'a>
+    where
+        Self: 'a,
+    {
+    }
+}
+
+pub struct Here;
+pub struct There<I>(PhantomData<I>);
+
+/// Finds the level of type `T` in the nested sequence of references, the index `I` is inferred.
+pub trait Pick<'a, T, I> {
+    fn pick(self) -> &'a mut T;
+}
+impl<'a, T, R> Pick<'a, T, Here> for (&'a mut T, R) {
+    fn pick(self) -> &'a mut T {
+        self.0
+    }
+}
+impl<'a, T, H, R: Pick<'a, T, I>, I> Pick<'a, T, There<I>> for (&'a mut H, R) {
+    fn pick(self) -> &'a mut T {
+        self.1.pick()
+    }
+}
+
+/// Finds the level of type `T` in the nested sequence, the index `I` is inferred.
+#[diagnostic::on_unimplemented(
+    message = "`{T}` is not a level of the base sequence",
+    label = "`with (..)` can only read the levels below the construct"
+)]
+pub trait Find<T, I> {
+    fn find(&self) -> &T;
+}
+impl<T, R> Find<T, Here> for (T, R) {
+    fn find(&self) -> &T {
+        &self.0
+    }
+}
+impl<T, H, R: Find<T, I>, I> Find<T, There<I>> for (H, R) {
+    fn find(&self) -> &T {
+        self.1.find()
+    }
+}
+
 impl<const I: u8, T> F<I, T> {
     pub fn define(self, value: T) -> D<I, T> {
         D::<I, T>(value)
@@ -435,6 +879,16 @@ impl<'a, T> Value<'a, T> {
     }
 }
 
+impl<'a, T> std::ops::Deref for Value<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Value::Val(ref v) => v,
+            Value::Ref(r) => r,
+        }
+    }
+}
+
 impl<'a, T: Clone> Value<'a, T> {
     pub fn get(&self) -> T {
         match self {
@@ -444,59 +898,1628 @@ impl<'a, T: Clone> Value<'a, T> {
     }
 }
 
-#[derive(Copy)]
 pub struct Getter<H, T>(fn(&H) -> Value<T>);
+impl<H, T> Copy for Getter<H, T> {}
 impl<H, T> Clone for Getter<H, T> {
     fn clone(&self) -> Self {
-        Getter(self.0)
+        *self
     }
 }
-#[derive(Copy)]
-pub struct Setter<H, T>(fn(&mut H, T));
-impl<H, T> Clone for Setter<H, T> {
+pub struct Setter<H, T, W = ()>(fn(&mut H, T) -> W);
+impl<H, T, W> Copy for Setter<H, T, W> {}
+impl<H, T, W> Clone for Setter<H, T, W> {
     fn clone(&self) -> Self {
-        Setter(self.0)
+        *self
     }
 }
 
-#[derive(Copy)]
-pub struct Prop<H, T> {
+/// The way the prop reads the value of its host.
+pub trait ReadAccess<H, T>: Copy {
+    fn get<'a>(&self, host: &'a H) -> Value<'a, T>;
+}
+
+/// The way the prop reads and writes the value of its host.
+pub trait Access<H, T>: ReadAccess<H, T> {
+    fn set(&self, host: &mut H, value: T);
+    fn stage(&self, host: &mut H, value: T);
+    fn commit(&self, host: &mut H);
+    fn commit_info(&self) -> PropCommit;
+}
+
+/// The way the writes of the prop are committed, see `set_many()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PropCommit {
+    /// Id of the `#[prop(commit = ..)]` hook, the props sharing the hook share the id
+    /// and the hook is called once. The props without the id are committed one by one.
+    pub hook: Option<u64>,
+    /// The value is staged through the `[get, set]` setter, it may depend on the other
+    /// props, so it is staged once more when all the values are in place.
+    pub restage: bool,
+}
+
+/// Function pointers generated by `prop!`.
+///
+/// The writes of the read-only props return `ReadOnly`, `Accessors<H, T, ReadOnly>`
+/// is not `Access`, see `PropRef`.
+pub struct Accessors<H, T, W = ()> {
     getter: Getter<H, T>,
-    setter: Setter<H, T>,
+    setter: Setter<H, T, W>,
+    stage: Setter<H, T, W>,
+    commit: fn(&mut H) -> W,
+    info: fn() -> PropCommit,
+}
+impl<H, T, W> Copy for Accessors<H, T, W> {}
+impl<H, T, W> Clone for Accessors<H, T, W> {
+    fn clone(&self) -> Self {
+        *self
+    }
 }
 
-pub trait TypeReference {
-    type Type;
+impl<H, T, W> ReadAccess<H, T> for Accessors<H, T, W> {
+    fn get<'a>(&self, host: &'a H) -> Value<'a, T> {
+        (self.getter.0)(host)
+    }
+}
+
+impl<H, T> Access<H, T> for Accessors<H, T> {
+    fn set(&self, host: &mut H, value: T) {
+        (self.setter.0)(host, value);
+    }
+    fn stage(&self, host: &mut H, value: T) {
+        (self.stage.0)(host, value);
+    }
+    fn commit(&self, host: &mut H) {
+        (self.commit)(host);
+    }
+    fn commit_info(&self) -> PropCommit {
+        (self.info)()
+    }
 }
 
-impl<H, T> Clone for Prop<H, T> {
+/// The `inner` prop of the value of the `outer` prop, see `Prop::then()`.
+pub struct Then<A, B, T> {
+    outer: A,
+    inner: B,
+    marker: PhantomData<fn(&T) -> T>,
+}
+impl<A: Copy, B: Copy, T> Copy for Then<A, B, T> {}
+impl<A: Copy, B: Copy, T> Clone for Then<A, B, T> {
     fn clone(&self) -> Self {
-        Self {
-            getter: self.getter.clone(),
-            setter: self.setter.clone(),
+        *self
+    }
+}
+
+// The nested value is read through the outer getter, modified and written
+// back through the outer setter, so it works with `[get, set]` props as well.
+impl<H, T: Clone + 'static, U: Clone, A: Access<H, T>, B: Access<T, U>> ReadAccess<H, U>
+    for Then<A, B, T>
+{
+    fn get<'a>(&self, host: &'a H) -> Value<'a, U> {
+        match self.outer.get(host) {
+            Value::Ref(value) => self.inner.get(value),
+            Value::Val(value) => Value::Val(self.inner.get(&value).get()),
         }
     }
 }
 
-impl<H, T> Prop<H, T> {
-    pub fn new(getter: fn(&H) -> Value<T>, setter: fn(&mut H, T)) -> Self {
-        Self {
-            getter: Getter(getter),
-            setter: Setter(setter),
+impl<H, T: Clone + 'static, U: Clone, A: Access<H, T>, B: Access<T, U>> Access<H, U>
+    for Then<A, B, T>
+{
+    fn set(&self, host: &mut H, value: U) {
+        let mut outer = self.outer.get(host).get();
+        self.inner.set(&mut outer, value);
+        self.outer.set(host, outer);
+    }
+    fn stage(&self, host: &mut H, value: U) {
+        let mut outer = self.outer.get(host).get();
+        self.inner.stage(&mut outer, value);
+        self.outer.stage(host, outer);
+    }
+    fn commit(&self, host: &mut H) {
+        let mut outer = self.outer.get(host).get();
+        self.inner.commit(&mut outer);
+        self.outer.stage(host, outer);
+        self.outer.commit(host);
+    }
+    // both hooks are called by the commit, it is shared only with the same pair
+    fn commit_info(&self) -> PropCommit {
+        let outer = self.outer.commit_info();
+        let inner = self.inner.commit_info();
+        PropCommit {
+            hook: outer
+                .hook
+                .zip(inner.hook)
+                .map(|(o, i)| (o ^ i.rotate_left(17)).wrapping_mul(0x100000001b3)),
+            restage: outer.restage || inner.restage,
         }
     }
-    pub fn get<'a>(&self, host: &'a H) -> Value<'a, T> {
-        (self.getter.0)(host)
+}
+
+/// Converted view of the prop, see `Prop::map()`.
+pub struct Map<A, T, G, S> {
+    prop: A,
+    get: G,
+    set: S,
+    marker: PhantomData<fn(&T) -> T>,
+}
+impl<A: Copy, T, G: Copy, S: Copy> Copy for Map<A, T, G, S> {}
+impl<A: Copy, T, G: Copy, S: Copy> Clone for Map<A, T, G, S> {
+    fn clone(&self) -> Self {
+        *self
     }
-    pub fn set(&self, host: &mut H, value: T) {
-        (self.setter.0)(host, value);
+}
+
+impl<H, T, U, A, G, S> ReadAccess<H, U> for Map<A, T, G, S>
+where
+    A: Access<H, T>,
+    G: Fn(&T) -> U + Copy,
+    S: Fn(U) -> T + Copy,
+{
+    fn get<'a>(&self, host: &'a H) -> Value<'a, U> {
+        Value::Val((self.get)(self.prop.get(host).as_ref()))
+    }
+}
+
+impl<H, T, U, A, G, S> Access<H, U> for Map<A, T, G, S>
+where
+    A: Access<H, T>,
+    G: Fn(&T) -> U + Copy,
+    S: Fn(U) -> T + Copy,
+{
+    fn set(&self, host: &mut H, value: U) {
+        self.prop.set(host, (self.set)(value));
+    }
+    fn stage(&self, host: &mut H, value: U) {
+        self.prop.stage(host, (self.set)(value));
+    }
+    fn commit(&self, host: &mut H) {
+        self.prop.commit(host);
+    }
+    fn commit_info(&self) -> PropCommit {
+        self.prop.commit_info()
+    }
+}
+
+/// Identity of the prop: the dotted path from the root type, `Node.position.x`,
+/// and the stable hash of it. Composed props keep the paths of all the parts.
+/// Props are compared and hashed by the id.
+#[derive(Clone, Copy)]
+pub struct PropId {
+    parts: [&'static str; PropId::MAX_PARTS],
+    len: usize,
+    hash: u64,
+}
+
+impl PropId {
+    /// Paths of the deeper compositions are dropped, the hash still includes them.
+    pub const MAX_PARTS: usize = 4;
+
+    /// FNV-1a of the path, it doesn't change between builds and platforms.
+    pub const fn new(path: &'static str) -> Self {
+        let bytes = path.as_bytes();
+        let mut hash = 0xcbf29ce484222325u64;
+        let mut idx = 0;
+        while idx < bytes.len() {
+            hash ^= bytes[idx] as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+            idx += 1;
+        }
+        let mut parts = [""; PropId::MAX_PARTS];
+        parts[0] = path;
+        PropId {
+            parts,
+            len: 1,
+            hash,
+        }
+    }
+    /// Path of the prop, `None` for the composed ones, see `parts()`.
+    pub const fn path(&self) -> Option<&'static str> {
+        if self.len == 1 {
+            Some(self.parts[0])
+        } else {
+            None
+        }
+    }
+    /// Paths of the composed prop, `["Node.position", "Vec2.x"]`.
+    pub fn parts(&self) -> &[&'static str] {
+        &self.parts[..self.len]
+    }
+    pub const fn as_u64(&self) -> u64 {
+        self.hash
+    }
+    /// Id of the composed prop, `prop!(Node.position).then(prop!(Vec2.x))`. The
+    /// paths of the inner prop follow the paths of the outer one.
+    pub const fn then(self, inner: PropId) -> PropId {
+        let mut parts = self.parts;
+        let mut len = self.len;
+        let mut idx = 0;
+        while idx < inner.len && len < PropId::MAX_PARTS {
+            parts[len] = inner.parts[idx];
+            len += 1;
+            idx += 1;
+        }
+        PropId {
+            parts,
+            len,
+            hash: (self.hash ^ inner.hash.rotate_left(17)).wrapping_mul(0x100000001b3),
+        }
+    }
+}
+
+impl PartialEq for PropId {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && self.parts() == other.parts()
+    }
+}
+impl Eq for PropId {}
+impl Hash for PropId {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        state.write_u64(self.hash)
+    }
+}
+impl std::fmt::Debug for PropId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PropId({self})")
+    }
+}
+impl std::fmt::Display for PropId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.parts().join(" -> "))
+    }
+}
+
+// Debug, PartialEq, Eq and Hash of the props by `PropId`
+macro_rules! impl_prop_identity {
+    ($name:ident<$($param:ident),*>) => {
+        impl<$($param),*> std::fmt::Debug for $name<$($param),*> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}({})", stringify!($name), self.id)
+            }
+        }
+        impl<$($param),*> PartialEq for $name<$($param),*> {
+            fn eq(&self, other: &Self) -> bool {
+                self.id == other.id
+            }
+        }
+        impl<$($param),*> Eq for $name<$($param),*> {}
+        impl<$($param),*> Hash for $name<$($param),*> {
+            fn hash<S: Hasher>(&self, state: &mut S) {
+                self.id.hash(state)
+            }
+        }
+    };
+}
+
+pub struct Prop<H, T, A = Accessors<H, T>> {
+    access: A,
+    id: PropId,
+    marker: PhantomData<fn(&H) -> T>,
+}
+impl_prop_identity!(Prop<H, T, A>);
+impl<H, T, A: Copy> Copy for Prop<H, T, A> {}
+
+pub trait TypeReference {
+    type Type;
+}
+
+impl<H, T, A: Copy> Clone for Prop<H, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H, T> Prop<H, T> {
+    /// The prop created by hand, identified by the `path`.
+    pub const fn new(
+        path: &'static str,
+        getter: fn(&H) -> Value<T>,
+        setter: fn(&mut H, T),
+    ) -> Self {
+        Self::from_access(
+            PropId::new(path),
+            Accessors {
+                getter: Getter(getter),
+                setter: Setter(setter),
+                stage: Setter(setter),
+                commit: |_| {},
+                info: PropCommit::default,
+            },
+        )
+    }
+    /// Splits the setter into the raw write (`stage`) and the invariants
+    /// restoration (`commit`), see `set_many()`.
+    pub const fn with_commit(mut self, stage: fn(&mut H, T), commit: fn(&mut H)) -> Self {
+        self.access.stage = Setter(stage);
+        self.access.commit = commit;
+        self
     }
     pub fn getter(&self) -> fn(&H) -> Value<T> {
-        self.getter.0.clone()
+        self.access.getter.0.clone()
     }
 
     pub fn setter(&self) -> fn(&mut H, T) {
-        self.setter.0.clone()
+        self.access.setter.0.clone()
+    }
+}
+
+impl<H, T, A> Prop<H, T, A> {
+    pub const fn with_id(mut self, id: PropId) -> Self {
+        self.id = id;
+        self
+    }
+    pub const fn id(&self) -> PropId {
+        self.id
+    }
+}
+
+impl<H, T, W> Prop<H, T, Accessors<H, T, W>> {
+    /// Dotted path of the prop, `Node.position.x`. Composed props have the paths
+    /// of all the parts instead, see `PropId::parts()`.
+    pub const fn path(&self) -> &'static str {
+        self.id.parts[0]
+    }
+}
+
+impl<H, T, A: ReadAccess<H, T>> Prop<H, T, A> {
+    pub fn get<'a>(&self, host: &'a H) -> Value<'a, T> {
+        self.access.get(host)
+    }
+}
+
+impl<H, T, A: Access<H, T>> Prop<H, T, A> {
+    pub const fn from_access(id: PropId, access: A) -> Self {
+        Self {
+            access,
+            id,
+            marker: PhantomData,
+        }
+    }
+    pub fn set(&self, host: &mut H, value: T) {
+        self.access.set(host, value);
+    }
+    /// Writes the value without calling the `#[prop(commit = ..)]` hook.
+    pub fn stage(&self, host: &mut H, value: T) {
+        self.access.stage(host, value);
+    }
+    /// Calls the `#[prop(commit = ..)]` hook of the prop, if any.
+    pub fn commit(&self, host: &mut H) {
+        self.access.commit(host);
+    }
+    /// Composes the prop with the prop of its value:
+    /// `prop!(Node.position).then(prop!(Vec2.x))` reads and writes `Node.position.x`.
+    ///
+    /// The composed accessors are stored in the prop to keep it `Copy`, so the
+    /// result is `Prop<Node, f32, Then<..>>` rather than `Prop<Node, f32>`. Use
+    /// `Prop<H, T, impl Access<H, T>>` or a generic `A: Access<H, T>` to pass it around.
+    pub fn then<U: Clone, B: Access<T, U>>(self, inner: Prop<T, U, B>) -> Prop<H, U, Then<A, B, T>>
+    where
+        T: Clone + 'static,
+    {
+        Prop::from_access(
+            self.id.then(inner.id),
+            Then {
+                outer: self.access,
+                inner: inner.access,
+                marker: PhantomData,
+            },
+        )
+    }
+    /// Exposes the prop as the value of another type, `get` converts the value
+    /// of the prop and `set` converts it back. Both can be closures as long as
+    /// they are `Copy`. The result is `Prop<H, U, Map<..>>`, see `then()`.
+    ///
+    /// The id is composed from the id of the prop and the fixed `"map"` part, so it
+    /// is stable between builds, but all the maps of the prop share it. Use `with_id()`
+    /// to tell them apart.
+    pub fn map<U, G, S>(self, get: G, set: S) -> Prop<H, U, Map<A, T, G, S>>
+    where
+        G: Fn(&T) -> U + Copy,
+        S: Fn(U) -> T + Copy,
+    {
+        Prop::from_access(
+            self.id.then(PropId::new("map")),
+            Map {
+                prop: self.access,
+                get,
+                set,
+                marker: PhantomData,
+            },
+        )
+    }
+}
+
+/// Read-only prop, `prop!(Rect.area)` for the `#[prop(get = area, ty = f32)]`
+/// or `area: f32 = [area]` props. There is no way to set the value.
+pub type PropRef<H, T> = Prop<H, T, Accessors<H, T, ReadOnly>>;
+
+impl<H, T> PropRef<H, T> {
+    /// The read-only prop created by hand, identified by the `path`.
+    pub const fn read_only(path: &'static str, getter: fn(&H) -> Value<T>) -> Self {
+        Prop {
+            access: Accessors {
+                getter: Getter(getter),
+                setter: Setter(|_, _| ReadOnly),
+                stage: Setter(|_, _| ReadOnly),
+                commit: |_| ReadOnly,
+                info: PropCommit::default,
+            },
+            id: PropId::new(path),
+            marker: PhantomData,
+        }
+    }
+    pub fn getter(&self) -> fn(&H) -> Value<T> {
+        self.access.getter.0
+    }
+}
+
+/// Writes of the prop, returned by the hidden `write_x` of the lookup props.
+#[doc(hidden)]
+pub struct PropWrite<H, T> {
+    set: fn(&mut H, T),
+    stage: fn(&mut H, T),
+    commit: fn(&mut H),
+    info: PropCommit,
+}
+impl<H, T> PropWrite<H, T> {
+    pub fn new(
+        set: fn(&mut H, T),
+        stage: fn(&mut H, T),
+        commit: fn(&mut H),
+        info: PropCommit,
+    ) -> Self {
+        PropWrite {
+            set,
+            stage,
+            commit,
+            info,
+        }
+    }
+    pub fn info(&self) -> PropCommit {
+        self.info
+    }
+    pub fn set(&self, host: &mut H, value: T) {
+        (self.set)(host, value)
+    }
+    pub fn stage(&self, host: &mut H, value: T) {
+        (self.stage)(host, value)
+    }
+    pub fn commit(&self, host: &mut H) {
+        (self.commit)(host)
+    }
+}
+
+/// Returned by the hidden `write_x` of the read-only props instead of `PropWrite`,
+/// turns `prop!` into `PropRef`.
+pub struct ReadOnly;
+impl ReadOnly {
+    #[doc(hidden)]
+    pub fn set<H, T>(&self, _: &mut H, _: T) -> ReadOnly {
+        ReadOnly
+    }
+    #[doc(hidden)]
+    pub fn stage<H, T>(&self, _: &mut H, _: T) -> ReadOnly {
+        ReadOnly
+    }
+    #[doc(hidden)]
+    pub fn commit<H>(&self, _: &mut H) -> ReadOnly {
+        ReadOnly
+    }
+    #[doc(hidden)]
+    pub fn info(&self) -> PropCommit {
+        PropCommit::default()
+    }
+}
+
+/// `prop!` expands to this, so it can be used in `const` and `static` items. The
+/// writes of the read-only props return `ReadOnly`, it makes the result `PropRef`.
+#[doc(hidden)]
+pub const fn build_prop<H, T, W>(
+    id: PropId,
+    getter: fn(&H) -> Value<T>,
+    setter: fn(&mut H, T) -> W,
+    stage: fn(&mut H, T) -> W,
+    commit: fn(&mut H) -> W,
+    info: fn() -> PropCommit,
+) -> Prop<H, T, Accessors<H, T, W>> {
+    Prop {
+        access: Accessors {
+            getter: Getter(getter),
+            setter: Setter(setter),
+            stage: Setter(stage),
+            commit,
+            info,
+        },
+        id,
+        marker: PhantomData,
+    }
+}
+
+/// Metadata of the prop for editors, `Props<Describe>`: `descriptors().meta_width()`
+pub struct PropMeta<T> {
+    pub name: &'static str,
+    /// `#[prop(range = 0.0..=1.0)]`, enforced by the setters and `construct!`
+    pub range: Option<PropRange<T>>,
+    /// `#[prop(step = 0.01)]`
+    pub step: Option<T>,
+    /// `#[prop(unit = "px")]`
+    pub unit: Option<&'static str>,
+    /// `#[prop(on_invalid = reject)]`: out of range values are ignored by the setters
+    /// instead of being clamped.
+    pub reject: bool,
+    /// `#[prop(tag = "style")]`
+    pub tags: &'static [&'static str],
+    /// `#[prop(group = "Layout")]`
+    pub group: Option<&'static str>,
+}
+
+/// Untyped prop description, see `Construct::props_tagged()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PropInfo {
+    /// The construct or segment the prop is declared on.
+    pub host: &'static str,
+    pub name: &'static str,
+    pub tags: &'static [&'static str],
+    pub group: Option<&'static str>,
+}
+
+impl PropInfo {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag)
+    }
+}
+
+/// Collects `ConstructItem::PROPS` of every level of the nested sequence.
+pub trait PropsSequence {
+    fn collect_props(props: &mut Vec<&'static PropInfo>);
+}
+
+impl PropsSequence for () {
+    fn collect_props(_: &mut Vec<&'static PropInfo>) {}
+}
+
+impl<H: ConstructItem, R: PropsSequence> PropsSequence for (H, R) {
+    fn collect_props(props: &mut Vec<&'static PropInfo>) {
+        props.extend(H::PROPS.iter());
+        R::collect_props(props);
+    }
+}
+
+pub struct PropRange<T> {
+    pub start: Bound<T>,
+    pub end: Bound<T>,
+}
+
+impl<T: Clone> PropRange<T> {
+    pub fn from_bounds(range: &impl RangeBounds<T>) -> Self {
+        Self {
+            start: range.start_bound().cloned(),
+            end: range.end_bound().cloned(),
+        }
+    }
+}
+
+impl<T: PartialOrd> PropRange<T> {
+    pub fn contains(&self, value: &T) -> bool {
+        (self.start.as_ref(), self.end.as_ref()).contains(value)
+    }
+}
+
+/// Clamps the value to the range. Returns `None` if the value is beyond the excluded
+/// bound, there is no value in the range to clamp it to.
+pub fn clamp_to_range<T: PartialOrd + Clone, R: RangeBounds<T>>(value: T, range: R) -> Option<T> {
+    let value = match range.start_bound() {
+        Bound::Included(start) if &value < start => start.clone(),
+        Bound::Excluded(start) if &value <= start => return None,
+        _ => value,
+    };
+    match range.end_bound() {
+        Bound::Included(end) if &value > end => Some(end.clone()),
+        Bound::Excluded(end) if &value >= end => None,
+        _ => Some(value),
+    }
+}
+
+/// Prop with the path going through containers: `prop!(List.items[3].label)`,
+/// `prop!(Node.parent?.position)`. The value may be missing, so the getter returns
+/// `Option` and the setters return `false` if nothing was set. Values are read
+/// through the containers by cloning.
+pub struct TryProp<H, T> {
+    getter: fn(&H) -> Option<Value<T>>,
+    setter: fn(&mut H, T) -> bool,
+    stage: fn(&mut H, T) -> bool,
+    commit: fn(&mut H),
+    id: PropId,
+}
+impl_prop_identity!(TryProp<H, T>);
+impl<H, T> Copy for TryProp<H, T> {}
+impl<H, T> Clone for TryProp<H, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<H, T> TryProp<H, T> {
+    /// The prop created by hand, identified by the `path`.
+    pub const fn new(
+        path: &'static str,
+        getter: fn(&H) -> Option<Value<T>>,
+        setter: fn(&mut H, T) -> bool,
+    ) -> Self {
+        Self {
+            getter,
+            setter,
+            stage: setter,
+            commit: |_| {},
+            id: PropId::new(path),
+        }
+    }
+    pub const fn with_commit(mut self, stage: fn(&mut H, T) -> bool, commit: fn(&mut H)) -> Self {
+        self.stage = stage;
+        self.commit = commit;
+        self
+    }
+    pub const fn with_id(mut self, id: PropId) -> Self {
+        self.id = id;
+        self
+    }
+    pub const fn id(&self) -> PropId {
+        self.id
+    }
+    /// Dotted path of the prop, `List.items[3].label`.
+    pub const fn path(&self) -> &'static str {
+        self.id.parts[0]
+    }
+    pub fn get<'a>(&self, host: &'a H) -> Option<Value<'a, T>> {
+        (self.getter)(host)
+    }
+    pub fn set(&self, host: &mut H, value: T) -> bool {
+        (self.setter)(host, value)
+    }
+    pub fn stage(&self, host: &mut H, value: T) -> bool {
+        (self.stage)(host, value)
+    }
+    pub fn commit(&self, host: &mut H) {
+        (self.commit)(host)
+    }
+}
+
+/// Containers the prop path can be indexed in: `prop!(List.items[3])`.
+pub trait PropIndex<I> {
+    type Output;
+    fn at(&self, index: I) -> Option<&Self::Output>;
+    fn at_mut(&mut self, index: I) -> Option<&mut Self::Output>;
+}
+
+impl<T> PropIndex<usize> for Vec<T> {
+    type Output = T;
+    fn at(&self, index: usize) -> Option<&T> {
+        self.get(index)
+    }
+    fn at_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.get_mut(index)
+    }
+}
+
+impl<T, const N: usize> PropIndex<usize> for [T; N] {
+    type Output = T;
+    fn at(&self, index: usize) -> Option<&T> {
+        self.get(index)
+    }
+    fn at_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.get_mut(index)
+    }
+}
+
+impl<T> PropIndex<usize> for VecDeque<T> {
+    type Output = T;
+    fn at(&self, index: usize) -> Option<&T> {
+        self.get(index)
+    }
+    fn at_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.get_mut(index)
+    }
+}
+
+impl<K: Hash + Eq, V> PropIndex<K> for HashMap<K, V> {
+    type Output = V;
+    fn at(&self, index: K) -> Option<&V> {
+        self.get(&index)
+    }
+    fn at_mut(&mut self, index: K) -> Option<&mut V> {
+        self.get_mut(&index)
+    }
+}
+
+impl<K: Ord, V> PropIndex<K> for BTreeMap<K, V> {
+    type Output = V;
+    fn at(&self, index: K) -> Option<&V> {
+        self.get(&index)
+    }
+    fn at_mut(&mut self, index: K) -> Option<&mut V> {
+        self.get_mut(&index)
+    }
+}
+
+/// Lets the prop path go through the pointers to the constructs: `Box<T>`,
+/// `Rc<RefCell<T>>`. Implemented for every construct by its derive.
+pub trait Through {
+    type Target;
+    fn through<R>(&self, f: impl FnOnce(&Self::Target) -> R) -> R;
+    fn through_mut<R>(&mut self, f: impl FnOnce(&mut Self::Target) -> R) -> R;
+}
+
+impl<T: Through> Through for Box<T> {
+    type Target = T::Target;
+    fn through<R>(&self, f: impl FnOnce(&Self::Target) -> R) -> R {
+        self.as_ref().through(f)
+    }
+    fn through_mut<R>(&mut self, f: impl FnOnce(&mut Self::Target) -> R) -> R {
+        self.as_mut().through_mut(f)
+    }
+}
+
+impl<T: Through> Through for Rc<RefCell<T>> {
+    type Target = T::Target;
+    fn through<R>(&self, f: impl FnOnce(&Self::Target) -> R) -> R {
+        self.borrow().through(f)
+    }
+    fn through_mut<R>(&mut self, f: impl FnOnce(&mut Self::Target) -> R) -> R {
+        self.borrow_mut().through_mut(f)
+    }
+}
+
+pub fn getters<T: ConstructItem>(item: &T) -> T::Getters<'_> {
+    <T::Getters<'_> as Getters<'_, T>>::from_ref(item)
+}
+
+pub fn setters<T: ConstructItem>(item: &mut T) -> T::Setters<'_> {
+    <T::Setters<'_> as Setters<'_, T>>::from_mut(item)
+}
+
+/// Stages all the values first and commits them after, so the result
+/// doesn't depend on the order of the props. Every commit hook is called once.
+///
+/// `[get, set]` props have no raw value to stage, their setters clamp against the
+/// props that are not staged yet. So their values are staged once more, in the same
+/// order, when all the values are in place.
+pub fn set_many<H, T: Clone, A: Access<H, T>, I: IntoIterator<Item = (Prop<H, T, A>, T)>>(
+    host: &mut H,
+    values: I,
+) {
+    let staged = values.into_iter().collect::<Vec<_>>();
+    for (prop, value) in staged.iter() {
+        prop.stage(host, value.clone());
+    }
+    for (prop, value) in staged.iter() {
+        if prop.access.commit_info().restage {
+            prop.stage(host, value.clone());
+        }
+    }
+    let mut hooks = vec![];
+    for (prop, _) in staged.iter() {
+        match prop.access.commit_info().hook {
+            Some(hook) if hooks.contains(&hook) => continue,
+            Some(hook) => hooks.push(hook),
+            None => {}
+        }
+        prop.commit(host);
+    }
+}
+
+// Animation
+pub trait Lerp {
+    /// Interpolates between `self` (at `t = 0.`) and `to` (at `t = 1.`).
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].lerp(&to[i], t))
+    }
+}
+
+/// Easing functions for `Tween` and `Track` keyframes. Every function maps
+/// `0.0..=1.0` onto `0.0..=1.0`.
+pub mod ease {
+    pub fn linear(t: f32) -> f32 {
+        t
+    }
+    pub fn quad_in(t: f32) -> f32 {
+        t * t
+    }
+    pub fn quad_out(t: f32) -> f32 {
+        t * (2. - t)
+    }
+    pub fn quad_in_out(t: f32) -> f32 {
+        if t < 0.5 {
+            2. * t * t
+        } else {
+            -1. + (4. - 2. * t) * t
+        }
+    }
+    pub fn cubic_in(t: f32) -> f32 {
+        t * t * t
+    }
+    pub fn cubic_out(t: f32) -> f32 {
+        let t = t - 1.;
+        t * t * t + 1.
+    }
+    pub fn cubic_in_out(t: f32) -> f32 {
+        if t < 0.5 {
+            4. * t * t * t
+        } else {
+            let t = 2. * t - 2.;
+            0.5 * t * t * t + 1.
+        }
+    }
+}
+
+/// Animates a single prop from one value to another.
+pub struct Tween<H, T, A = Accessors<H, T>> {
+    prop: Prop<H, T, A>,
+    from: T,
+    to: T,
+    ease: fn(f32) -> f32,
+}
+
+impl<H, T: Clone, A: Copy> Clone for Tween<H, T, A> {
+    fn clone(&self) -> Self {
+        Self {
+            prop: self.prop,
+            from: self.from.clone(),
+            to: self.to.clone(),
+            ease: self.ease,
+        }
+    }
+}
+
+impl<H, T: Lerp, A: Access<H, T>> Tween<H, T, A> {
+    pub fn new(prop: Prop<H, T, A>, from: T, to: T) -> Self {
+        Self {
+            prop,
+            from,
+            to,
+            ease: ease::linear,
+        }
+    }
+    pub fn with_ease(mut self, ease: fn(f32) -> f32) -> Self {
+        self.ease = ease;
+        self
+    }
+    pub fn prop(&self) -> Prop<H, T, A> {
+        self.prop
+    }
+    /// Returns the value at `t`, `t` is clamped to `0.0..=1.0`.
+    pub fn value(&self, t: f32) -> T {
+        self.from.lerp(&self.to, (self.ease)(t.clamp(0., 1.)))
+    }
+    /// Sets the value at `t` to the `host` using the prop's setter.
+    pub fn sample(&self, host: &mut H, t: f32) {
+        self.prop.set(host, self.value(t));
+    }
+}
+
+pub struct Keyframe<T> {
+    pub time: f32,
+    pub value: T,
+    /// Easing of the segment that ends at this keyframe.
+    pub ease: fn(f32) -> f32,
+}
+
+/// Keyframed animation of a single prop.
+pub struct Track<H, T, A = Accessors<H, T>> {
+    prop: Prop<H, T, A>,
+    keys: Vec<Keyframe<T>>,
+}
+
+impl<H, T: Lerp + Clone, A: Access<H, T>> Track<H, T, A> {
+    pub fn new(prop: Prop<H, T, A>) -> Self {
+        Self { prop, keys: vec![] }
+    }
+    pub fn key(self, time: f32, value: T) -> Self {
+        self.key_eased(time, value, ease::linear)
+    }
+    pub fn key_eased(mut self, time: f32, value: T, ease: fn(f32) -> f32) -> Self {
+        let idx = self.keys.partition_point(|k| k.time <= time);
+        self.keys.insert(idx, Keyframe { time, value, ease });
+        self
+    }
+    pub fn prop(&self) -> Prop<H, T, A> {
+        self.prop
+    }
+    pub fn keys(&self) -> &[Keyframe<T>] {
+        &self.keys
+    }
+    /// Returns the value at `time`, or `None` if the track has no keyframes.
+    /// Before the first and after the last keyframe the edge values are held.
+    pub fn value(&self, time: f32) -> Option<T> {
+        let next = self.keys.partition_point(|k| k.time <= time);
+        if next == 0 {
+            return self.keys.first().map(|k| k.value.clone());
+        }
+        if next == self.keys.len() {
+            return self.keys.last().map(|k| k.value.clone());
+        }
+        let (from, to) = (&self.keys[next - 1], &self.keys[next]);
+        let t = (time - from.time) / (to.time - from.time);
+        Some(from.value.lerp(&to.value, (to.ease)(t)))
+    }
+}
+
+/// Anything that can be applied to the `H` at some point of time.
+pub trait Sample<H> {
+    fn sample(&self, host: &mut H, time: f32);
+    fn duration(&self) -> f32;
+}
+
+impl<H, T: Lerp + Clone, A: Access<H, T>> Sample<H> for Track<H, T, A> {
+    fn sample(&self, host: &mut H, time: f32) {
+        if let Some(value) = self.value(time) {
+            self.prop.set(host, value);
+        }
+    }
+    fn duration(&self) -> f32 {
+        self.keys.last().map(|k| k.time).unwrap_or(0.)
+    }
+}
+
+impl<H, T: Lerp, A: Access<H, T>> Sample<H> for Tween<H, T, A> {
+    fn sample(&self, host: &mut H, time: f32) {
+        Tween::sample(self, host, time)
+    }
+    fn duration(&self) -> f32 {
+        1.
+    }
+}
+
+/// A set of tracks animating the props of the same host.
+pub struct Timeline<H> {
+    tracks: Vec<Box<dyn Sample<H>>>,
+}
+
+impl<H> Default for Timeline<H> {
+    fn default() -> Self {
+        Self { tracks: vec![] }
+    }
+}
+
+impl<H> Timeline<H> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn track<S: Sample<H> + 'static>(mut self, track: S) -> Self {
+        self.add(track);
+        self
+    }
+    pub fn add<S: Sample<H> + 'static>(&mut self, track: S) {
+        self.tracks.push(Box::new(track));
+    }
+    /// The time of the latest keyframe across all tracks.
+    pub fn duration(&self) -> f32 {
+        self.tracks.iter().map(|t| t.duration()).fold(0., f32::max)
+    }
+    pub fn sample(&self, host: &mut H, time: f32) {
+        for track in self.tracks.iter() {
+            track.sample(host, time);
+        }
+    }
+}
+
+// History
+trait Edit<H> {
+    fn undo(&self, host: &mut H);
+    fn redo(&self, host: &mut H);
+}
+
+struct PropEdit<H, T, A> {
+    prop: Prop<H, T, A>,
+    old: T,
+    new: T,
+}
+
+impl<H, T: Clone, A: Access<H, T>> Edit<H> for PropEdit<H, T, A> {
+    fn undo(&self, host: &mut H) {
+        self.prop.set(host, self.old.clone());
+    }
+    fn redo(&self, host: &mut H) {
+        self.prop.set(host, self.new.clone());
+    }
+}
+
+/// Undo/redo stack of the props set to `H`. Both edits and reverts go
+/// through the prop's setter.
+pub struct PropHistory<H> {
+    undo: Vec<Vec<Box<dyn Edit<H>>>>,
+    redo: Vec<Vec<Box<dyn Edit<H>>>>,
+    transaction: Vec<Box<dyn Edit<H>>>,
+    depth: usize,
+}
+
+impl<H> Default for PropHistory<H> {
+    fn default() -> Self {
+        Self {
+            undo: vec![],
+            redo: vec![],
+            transaction: vec![],
+            depth: 0,
+        }
+    }
+}
+
+impl<H: 'static> PropHistory<H> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the current value through the getter, sets the new one and records the edit.
+    pub fn set<T: Clone + 'static, A: Access<H, T> + 'static>(
+        &mut self,
+        host: &mut H,
+        prop: Prop<H, T, A>,
+        value: T,
+    ) {
+        let old = prop.get(host).get();
+        prop.set(host, value.clone());
+        self.redo.clear();
+        let edit = Box::new(PropEdit {
+            prop,
+            old,
+            new: value,
+        });
+        if self.depth > 0 {
+            self.transaction.push(edit);
+        } else {
+            self.undo.push(vec![edit]);
+        }
+    }
+
+    /// Starts grouping sets into a single undo step. Transactions can be nested,
+    /// the group is closed by the outermost `commit()`.
+    pub fn begin(&mut self) {
+        self.depth += 1;
+    }
+
+    pub fn commit(&mut self) {
+        if self.depth == 0 {
+            return;
+        }
+        self.depth -= 1;
+        if self.depth == 0 && !self.transaction.is_empty() {
+            self.undo.push(std::mem::take(&mut self.transaction));
+        }
+    }
+
+    /// Runs `edit` inside of `begin()`/`commit()`.
+    pub fn transaction<F: FnOnce(&mut Self, &mut H)>(&mut self, host: &mut H, edit: F) {
+        self.begin();
+        edit(self, host);
+        self.commit();
+    }
+
+    /// Reverts the last step. Open transactions are committed first.
+    pub fn undo(&mut self, host: &mut H) -> bool {
+        while self.depth > 0 {
+            self.commit();
+        }
+        let Some(step) = self.undo.pop() else {
+            return false;
+        };
+        for edit in step.iter().rev() {
+            edit.undo(host);
+        }
+        self.redo.push(step);
+        true
+    }
+
+    pub fn redo(&mut self, host: &mut H) -> bool {
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+        for edit in step.iter() {
+            edit.redo(host);
+        }
+        self.undo.push(step);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty() || !self.transaction.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.transaction.clear();
+        self.depth = 0;
+    }
+}
+
+// Diff
+/// Single differing prop, applies the new value when called.
+pub struct Change<H> {
+    path: String,
+    apply: Box<dyn Fn(&mut H)>,
+}
+
+impl<H: 'static> Change<H> {
+    pub fn new<F: Fn(&mut H) + 'static>(path: impl Into<String>, apply: F) -> Self {
+        Self {
+            path: path.into(),
+            apply: Box::new(apply),
+        }
+    }
+    /// Lifts the change of the `#[prop(construct)]` field to its host.
+    pub fn nest<P: 'static>(self, name: &str, field: fn(&mut P) -> &mut H) -> Change<P> {
+        let apply = self.apply;
+        Change {
+            path: format!("{name}.{}", self.path),
+            apply: Box::new(move |host| apply(field(host))),
+        }
+    }
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    pub fn apply(&self, host: &mut H) {
+        (self.apply)(host)
+    }
+}
+
+/// Implemented by `#[derive(Construct)]`, `#[derive(Segment)]` and friends
+/// when every prop is `PartialEq + Clone`.
+pub trait Diff: Sized {
+    fn diff(from: &Self, to: &Self, changes: &mut Vec<Change<Self>>);
+}
+
+impl Diff for () {
+    fn diff(_: &Self, _: &Self, _: &mut Vec<Change<Self>>) {}
+}
+
+impl<T: PartialEq + Clone + 'static, const N: usize> Diff for [T; N] {
+    fn diff(from: &Self, to: &Self, changes: &mut Vec<Change<Self>>) {
+        for (idx, (a, b)) in from.iter().zip(to.iter()).enumerate() {
+            if a != b {
+                let value = b.clone();
+                changes.push(Change::new(idx.to_string(), move |host: &mut Self| {
+                    host[idx] = value.clone()
+                }));
+            }
+        }
+    }
+}
+
+/// Only the props of two present segments are compared, the segment itself
+/// is never added or removed by the patch.
+impl<T: Diff + 'static> Diff for Option<T> {
+    fn diff(from: &Self, to: &Self, changes: &mut Vec<Change<Self>>) {
+        let (Some(from), Some(to)) = (from, to) else {
+            return;
+        };
+        let mut inner = vec![];
+        T::diff(from, to, &mut inner);
+        changes.extend(inner.into_iter().map(|c| {
+            let apply = c.apply;
+            Change::new(c.path, move |host: &mut Self| {
+                if let Some(host) = host {
+                    apply(host)
+                }
+            })
+        }));
+    }
+}
+
+pub trait DiffSequence: Nested {
+    type Changes;
+    fn diff_nested<'a>(from: Self::Ref<'a>, to: Self::Ref<'a>) -> Self::Changes
+    where
+        Self: 'a;
+    fn apply_nested<'a>(changes: &Self::Changes, to: Self::Mut<'a>)
+    where
+        Self: 'a;
+    fn paths<'a>(changes: &'a Self::Changes, out: &mut Vec<&'a str>);
+}
+
+impl DiffSequence for () {
+    type Changes = ();
+    fn diff_nested<'a>(_: Self::Ref<'a>, _: Self::Ref<'a>) -> Self::Changes
+    where
+        Self: 'a,
+    {
+    }
+    fn apply_nested<'a>(_: &Self::Changes, _: Self::Mut<'a>)
+    where
+        Self: 'a,
+    {
+    }
+    fn paths<'a>(_: &'a Self::Changes, _: &mut Vec<&'a str>) {}
+}
+
+impl<H: Diff, R: DiffSequence> DiffSequence for (H, R) {
+    type Changes = (Vec<Change<H>>, R::Changes);
+    fn diff_nested<'a>(from: Self::Ref<'a>, to: Self::Ref<'a>) -> Self::Changes
+    where
+        Self: 'a,
+    {
+        let mut changes = vec![];
+        H::diff(from.0, to.0, &mut changes);
+        (changes, R::diff_nested(from.1, to.1))
+    }
+    fn apply_nested<'a>(changes: &Self::Changes, to: Self::Mut<'a>)
+    where
+        Self: 'a,
+    {
+        let (host, rest) = to;
+        for change in changes.0.iter() {
+            (change.apply)(host);
+        }
+        R::apply_nested(&changes.1, rest);
+    }
+    fn paths<'a>(changes: &'a Self::Changes, out: &mut Vec<&'a str>) {
+        out.extend(changes.0.iter().map(|c| c.path.as_str()));
+        R::paths(&changes.1, out);
+    }
+}
+
+/// Differing props of two sequences of `C`, see `diff()`.
+pub struct Patch<C: Construct>
+where
+    C::NestedSequence: DiffSequence,
+{
+    changes: <C::NestedSequence as DiffSequence>::Changes,
+}
+
+impl<C: Construct> Patch<C>
+where
+    C::NestedSequence: Unflattern + DiffSequence,
+{
+    /// Sets every differing prop to the `target` sequence.
+    pub fn apply(&self, target: &mut <C::NestedSequence as Flattern>::Output) {
+        let target = <C::NestedSequence as Unflattern>::unflattern_mut(target);
+        <C::NestedSequence as DiffSequence>::apply_nested(&self.changes, target);
+    }
+    /// Paths of the differing props, starting from the top of the sequence.
+    pub fn paths(&self) -> Vec<&str> {
+        let mut out = vec![];
+        <C::NestedSequence as DiffSequence>::paths(&self.changes, &mut out);
+        out
+    }
+    pub fn len(&self) -> usize {
+        self.paths().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Compares two sequences of `C` prop by prop across every level of the sequence.
+pub fn diff<C: Construct>(
+    from: &<C::NestedSequence as Flattern>::Output,
+    to: &<C::NestedSequence as Flattern>::Output,
+) -> Patch<C>
+where
+    C::NestedSequence: Unflattern + DiffSequence,
+{
+    let from = <C::NestedSequence as Unflattern>::unflattern_ref(from);
+    let to = <C::NestedSequence as Unflattern>::unflattern_ref(to);
+    Patch {
+        changes: <C::NestedSequence as DiffSequence>::diff_nested(from, to),
+    }
+}
+
+// Reset
+/// Implemented by `#[derive(Construct)]`, `#[derive(Segment)]` and friends. Restores
+/// every param-backed field to its default value. Required and `#[param(skip)]` params
+/// are left alone unless marked with `#[param(reset)]` or `#[param(reset = value)]`.
+/// Custom constructors are called again with default params, so they only implement
+/// `Reset` when there is no required params.
+pub trait Reset {
+    fn reset_defaults(&mut self);
+}
+
+impl Reset for () {
+    fn reset_defaults(&mut self) {}
+}
+
+impl<T: Reset> Reset for Option<T> {
+    fn reset_defaults(&mut self) {
+        if let Some(item) = self {
+            item.reset_defaults();
+        }
+    }
+}
+
+pub trait ResetSequence: Nested {
+    fn reset_nested<'a>(seq: Self::Mut<'a>)
+    where
+        Self: 'a;
+}
+
+impl ResetSequence for () {
+    fn reset_nested<'a>(_: Self::Mut<'a>)
+    where
+        Self: 'a,
+    {
+    }
+}
+
+impl<H: Reset, R: ResetSequence> ResetSequence for (H, R) {
+    fn reset_nested<'a>(seq: Self::Mut<'a>)
+    where
+        Self: 'a,
+    {
+        let (host, rest) = seq;
+        host.reset_defaults();
+        R::reset_nested(rest);
+    }
+}
+
+/// Resets every level of the sequence of `C` to param defaults.
+pub fn reset<C: Construct>(seq: &mut <C::NestedSequence as Flattern>::Output)
+where
+    C::NestedSequence: Unflattern + ResetSequence,
+{
+    let seq = <C::NestedSequence as Unflattern>::unflattern_mut(seq);
+    <C::NestedSequence as ResetSequence>::reset_nested(seq);
+}
+
+// Visit
+/// Typed callbacks for every prop of the value, see `visit_props()`.
+pub trait PropVisitor {
+    fn visit<T: 'static>(&mut self, name: &str, value: &T);
+    /// Called for the `#[prop(construct)]` props before their own props are
+    /// visited. Returning `false` visits the value as the whole instead.
+    fn enter(&mut self, name: &str) -> bool {
+        let _ = name;
+        true
+    }
+    fn exit(&mut self, name: &str) {
+        let _ = name;
+    }
+    /// Called before every level of the sequence, see `visit_sequence()`.
+    fn level(&mut self, host: &'static str) {
+        let _ = host;
+    }
+}
+
+/// Mutable version of `PropVisitor`. `[get, set]` props and the props with
+/// `commit` or `range` are written back through their setters, read-only props
+/// are skipped.
+pub trait PropVisitorMut {
+    fn visit<T: 'static>(&mut self, name: &str, value: &mut T);
+    fn enter(&mut self, name: &str) -> bool {
+        let _ = name;
+        true
+    }
+    fn exit(&mut self, name: &str) {
+        let _ = name;
+    }
+    fn level(&mut self, host: &'static str) {
+        let _ = host;
+    }
+}
+
+/// Implemented by `#[derive(Construct)]`, `#[derive(Segment)]` and `derive_construct!`.
+pub trait VisitProps {
+    const HOST: &'static str;
+    fn visit_props<V: PropVisitor>(&self, visitor: &mut V);
+    fn visit_props_mut<V: PropVisitorMut>(&mut self, visitor: &mut V);
+}
+
+impl<T: 'static, const N: usize> VisitProps for [T; N] {
+    const HOST: &'static str = "array";
+    fn visit_props<V: PropVisitor>(&self, visitor: &mut V) {
+        for (idx, value) in self.iter().enumerate() {
+            visitor.visit(&idx.to_string(), value);
+        }
+    }
+    fn visit_props_mut<V: PropVisitorMut>(&mut self, visitor: &mut V) {
+        for (idx, value) in self.iter_mut().enumerate() {
+            visitor.visit(&idx.to_string(), value);
+        }
+    }
+}
+
+impl<T: VisitProps> VisitProps for Option<T> {
+    const HOST: &'static str = T::HOST;
+    fn visit_props<V: PropVisitor>(&self, visitor: &mut V) {
+        if let Some(host) = self {
+            host.visit_props(visitor);
+        }
+    }
+    fn visit_props_mut<V: PropVisitorMut>(&mut self, visitor: &mut V) {
+        if let Some(host) = self {
+            host.visit_props_mut(visitor);
+        }
+    }
+}
+
+pub fn visit_props<T: VisitProps, V: PropVisitor>(host: &T, visitor: &mut V) {
+    host.visit_props(visitor)
+}
+
+pub fn visit_props_mut<T: VisitProps, V: PropVisitorMut>(host: &mut T, visitor: &mut V) {
+    host.visit_props_mut(visitor)
+}
+
+/// Visits every level of the nested sequence.
+pub trait VisitSequence: Nested {
+    fn visit_nested<'a, V: PropVisitor>(seq: Self::Ref<'a>, visitor: &mut V)
+    where
+        Self: 'a;
+    fn visit_nested_mut<'a, V: PropVisitorMut>(seq: Self::Mut<'a>, visitor: &mut V)
+    where
+        Self: 'a;
+}
+
+impl VisitSequence for () {
+    fn visit_nested<'a, V: PropVisitor>(_: Self::Ref<'a>, _: &mut V)
+    where
+        Self: 'a,
+    {
+    }
+    fn visit_nested_mut<'a, V: PropVisitorMut>(_: Self::Mut<'a>, _: &mut V)
+    where
+        Self: 'a,
+    {
+    }
+}
+
+impl<H: VisitProps, R: VisitSequence> VisitSequence for (H, R) {
+    fn visit_nested<'a, V: PropVisitor>(seq: Self::Ref<'a>, visitor: &mut V)
+    where
+        Self: 'a,
+    {
+        let (host, rest) = seq;
+        visitor.level(H::HOST);
+        host.visit_props(visitor);
+        R::visit_nested(rest, visitor);
+    }
+    fn visit_nested_mut<'a, V: PropVisitorMut>(seq: Self::Mut<'a>, visitor: &mut V)
+    where
+        Self: 'a,
+    {
+        let (host, rest) = seq;
+        visitor.level(H::HOST);
+        host.visit_props_mut(visitor);
+        R::visit_nested_mut(rest, visitor);
+    }
+}
+
+/// Visits the props of every level of the sequence of `C`, from `C` to the base.
+pub fn visit_sequence<C: Construct, V: PropVisitor>(
+    seq: &<C::NestedSequence as Flattern>::Output,
+    visitor: &mut V,
+) where
+    C::NestedSequence: Unflattern + VisitSequence,
+{
+    let seq = <C::NestedSequence as Unflattern>::unflattern_ref(seq);
+    <C::NestedSequence as VisitSequence>::visit_nested(seq, visitor);
+}
+
+pub fn visit_sequence_mut<C: Construct, V: PropVisitorMut>(
+    seq: &mut <C::NestedSequence as Flattern>::Output,
+    visitor: &mut V,
+) where
+    C::NestedSequence: Unflattern + VisitSequence,
+{
+    let seq = <C::NestedSequence as Unflattern>::unflattern_mut(seq);
+    <C::NestedSequence as VisitSequence>::visit_nested_mut(seq, visitor);
+}
+
+// Debug
+/// Prints every level of the nested sequence, joined with ` -> `.
+pub trait DebugSequence: Nested {
+    const IS_EMPTY: bool;
+    fn fmt_nested<'a>(seq: Self::Ref<'a>, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    where
+        Self: 'a;
+}
+
+impl DebugSequence for () {
+    const IS_EMPTY: bool = true;
+    fn fmt_nested<'a>(_: Self::Ref<'a>, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    where
+        Self: 'a,
+    {
+        Ok(())
+    }
+}
+
+impl<H: std::fmt::Debug, R: DebugSequence> DebugSequence for (H, R) {
+    const IS_EMPTY: bool = false;
+    fn fmt_nested<'a>(seq: Self::Ref<'a>, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    where
+        Self: 'a,
+    {
+        let (host, rest) = seq;
+        std::fmt::Debug::fmt(host, f)?;
+        if !R::IS_EMPTY {
+            f.write_str(" -> ")?;
+        }
+        R::fmt_nested(rest, f)
+    }
+}
+
+/// `Debug` of the whole sequence, `SequenceDebug::<Button>::new(&button)` prints
+/// `Button { .. } -> Input { .. } -> Rect { .. }`. Items use `#[construct(debug)]`.
+pub struct SequenceDebug<'a, C: Construct>(&'a <C::NestedSequence as Flattern>::Output);
+
+impl<'a, C: Construct> SequenceDebug<'a, C> {
+    pub fn new(seq: &'a <C::NestedSequence as Flattern>::Output) -> Self {
+        Self(seq)
+    }
+}
+
+impl<'a, C: Construct> std::fmt::Debug for SequenceDebug<'a, C>
+where
+    C::NestedSequence: Unflattern + DebugSequence,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let seq = <C::NestedSequence as Unflattern>::unflattern_ref(self.0);
+        <C::NestedSequence as DebugSequence>::fmt_nested(seq, f)
+    }
+}
+
+// Update
+/// Links the param to the prop with the same name, so `update!` can write
+/// the param to the existing sequence.
+pub trait ParamProp {
+    type Host: 'static;
+    type Value;
+    fn stage(host: &mut Self::Host, value: Self::Value);
+    fn commit(host: &mut Self::Host);
+}
+
+/// Params of the optional segment, `update!` skips them when the segment is missing.
+impl<N: ParamProp> ParamProp for Option<N> {
+    type Host = Option<N::Host>;
+    type Value = N::Value;
+    fn stage(host: &mut Self::Host, value: Self::Value) {
+        if let Some(host) = host {
+            N::stage(host, value)
+        }
+    }
+    fn commit(host: &mut Self::Host) {
+        if let Some(host) = host {
+            N::commit(host)
+        }
+    }
+}
+
+/// Re-runs the constructor of the item with the current prop values, see `update!(.., rebuild)`.
+/// It is a no-op for derived items, their invariants are kept by the commit hooks.
+pub trait Rebuild {
+    fn rebuild(&mut self);
+}
+
+impl<T: Rebuild> Rebuild for Option<T> {
+    fn rebuild(&mut self) {
+        if let Some(item) = self {
+            item.rebuild();
+        }
+    }
+}
+
+impl<N: ParamProp<Value = T>, T> Param<N, T> {
+    pub fn stage<'a, S: Pick<'a, N::Host, I>, I>(&self, seq: S, value: T) {
+        N::stage(seq.pick(), value)
+    }
+    pub fn commit<'a, S: Pick<'a, N::Host, I>, I>(&self, seq: S) {
+        N::commit(seq.pick())
+    }
+    /// Rebuilds the level this param belongs to, unless it is already in `rebuilt`.
+    pub fn rebuild<'a, S: Pick<'a, N::Host, I>, I>(&self, seq: S, rebuilt: &mut Vec<TypeId>)
+    where
+        N::Host: Rebuild,
+    {
+        let id = TypeId::of::<N::Host>();
+        if !rebuilt.contains(&id) {
+            rebuilt.push(id);
+            seq.pick().rebuild();
+        }
     }
 }
 